@@ -1,96 +1,990 @@
-use crate::stats::{StatsCollector, SystemStats};
-use crate::topology::ZenTopology;
+use std::collections::HashMap;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Collectors, Columns, Config};
+use crate::event::Waker;
+use crate::export::{Sink, SinkWorker};
+use crate::irq::{InterruptCollector, InterruptStats};
+use crate::record::{Recorder, Replayer};
+use crate::remote::{ClusterHost, HostView, RemoteSource};
+use crate::stats::{
+    Background, CgroupCollector, CgroupUsage, CpuDetails, DetailCollector, GroupKind,
+    GuestPlacement, History, LiveSource, Playback, StatsSource, SystemStats, VcpuScanner,
+};
+use crate::settings::Settings;
+use crate::topology::{CpuCore, ZenTopology};
 
 /// View mode for CPU display
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ViewMode {
     Core,
     Ccd,
     Nps,
+    Cgroup,
+    Irq,
+    /// Only meaningful for multi-host sessions, so never persisted or requested
+    #[serde(skip)]
+    #[value(skip)]
+    Cluster,
 }
 
+/// Refresh intervals selectable with faster/slower, in milliseconds
+const REFRESH_STEPS_MS: [u64; 9] = [100, 200, 250, 500, 1000, 2000, 3000, 5000, 10000];
+
+/// How long a notice stays in the header
+const NOTICE_DURATION: Duration = Duration::from_secs(3);
+
 /// Display density mode
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DisplayMode {
     #[default]
-    Full,    // One core per line (original)
-    Compact, // Multi-column, 16 cores per row
-    Heatmap, // Ultra-dense block characters, 64 cores per row
+    Full,      // One core per line (original)
+    Compact,   // Multi-column, 16 cores per row
+    Heatmap,   // Ultra-dense block characters, 64 cores per row
+    Sparkline, // Usage history sparkline per core/group
+    Waterfall, // CPU x time heatmap, one column per sample
+}
+
+/// Scroll position of a pane, in the scroll units of the view drawn in it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Scroll {
+    /// First content line shown
+    pub offset: usize,
+    /// Lines of content
+    pub content: usize,
+    /// Lines the pane shows at once
+    pub viewport: usize,
+}
+
+impl Scroll {
+    /// Offset that puts the last content line at the bottom of the pane
+    pub fn max_offset(&self) -> usize {
+        self.content.saturating_sub(self.viewport)
+    }
 }
 
 /// Application state
 pub struct App {
     pub topology: ZenTopology,
     pub stats: SystemStats,
+    pub history: History,
+    pub cgroups: Vec<CgroupUsage>,
+    pub guests: Vec<GuestPlacement>,
+    pub interrupts: InterruptStats,
+    /// Time breakdown, temperatures and busiest processes for the detail panel
+    pub details: CpuDetails,
     pub view_mode: ViewMode,
     pub display_mode: DisplayMode,
     pub show_smt: bool,
+    pub show_vcpus: bool,
     pub show_help: bool,
+    /// Scroll position of the help overlay
+    pub help_scroll: Scroll,
     pub should_quit: bool,
-    pub scroll_offset: usize,
-    collector: StatsCollector,
+    /// Scroll state as last drawn: the left or only pane, then the right socket pane
+    pub scroll: [Scroll; 2],
+    /// CPU selected for drill-down views
+    pub selected_cpu: usize,
+    /// Group selected in CCD/NPS views (position in the topology group list)
+    pub selected_group: usize,
+    /// Show the full-height history chart for the selection
+    pub show_chart: bool,
+    /// Show the detail panel for the selected CPU
+    pub show_detail: bool,
+    /// Aggregate steal percentage above which the header shows a warning
+    pub steal_threshold: f32,
+    /// Host highlighted in the cluster overview
+    pub selected_host: usize,
+    /// Socket pane that has focus in the split view
+    pub focused_socket: usize,
+    /// Sampling period, adjustable at runtime
+    pub refresh_interval: Duration,
+    /// Keep showing the current snapshot; new samples are still recorded and exported
+    pub frozen: bool,
+    /// Optional columns of full-mode CPU bars
+    pub columns: Columns,
+    /// Active color theme, as a name or path `Theme::load` accepts
+    pub theme: String,
+    /// Only show this socket (`--socket`)
+    pub socket: Option<usize>,
+    /// Only show these CPUs (`--cpus`), sorted
+    pub cpus: Option<Vec<usize>>,
+    collectors: Collectors,
+    /// Config file contents and location, for "save settings"
+    settings: Settings,
+    settings_path: Option<PathBuf>,
+    /// Short-lived message for the header, e.g. after saving settings
+    notice: Option<(String, Instant)>,
+    /// Host whose data is in `topology`/`stats`/`history`
+    active_host: usize,
+    /// Snapshot source; `None` while cluster hosts supply the data
+    source: Option<Box<dyn StatsSource>>,
+    /// Hosts of a multi-host session, in connection order
+    hosts: Vec<ClusterHost>,
+    recorder: Option<Recorder>,
+    sinks: Option<SinkWorker>,
+    cgroup_worker: Background<Vec<CgroupUsage>>,
+    vcpu_worker: Background<Vec<GuestPlacement>>,
+    interrupt_worker: Background<InterruptStats>,
+    detail_worker: Background<CpuDetails>,
 }
 
 impl App {
-    pub fn new(topology: ZenTopology) -> Self {
-        let mut collector = StatsCollector::new();
-        let stats = collector.refresh();
+    /// Sample this host; the first snapshot arrives once the sampler has
+    /// primed, and each one wakes `waker`
+    pub fn new(topology: ZenTopology, config: &Config, waker: Waker) -> Self {
+        let interval = Duration::from_millis(config.refresh_rate);
+        let source = LiveSource::spawn(config.source, &topology, interval, waker);
+        Self::with_source(topology, SystemStats::default(), Some(Box::new(source)), config)
+    }
+
+    /// Drive the UI from a recording instead of live sampling
+    pub fn replay(replayer: Replayer, config: &Config) -> Self {
+        let topology = replayer.topology().clone();
+        let stats = replayer.current();
+        Self::with_source(topology, stats, Some(Box::new(replayer)), config)
+    }
 
-        Self {
+    /// Drive the UI from a remote agent
+    pub fn remote(
+        topology: ZenTopology,
+        stats: SystemStats,
+        source: RemoteSource,
+        config: &Config,
+    ) -> Self {
+        Self::with_source(topology, stats, Some(Box::new(source)), config)
+    }
+
+    /// Drive the UI from several remote agents, starting in the cluster overview
+    pub fn cluster(hosts: Vec<ClusterHost>, config: &Config) -> Self {
+        let mut app =
+            Self::with_source(ZenTopology::default(), SystemStats::default(), None, config);
+        // Hosts differ in shape, so socket and CPU filters do not carry over
+        app.socket = None;
+        app.cpus = None;
+        app.hosts = hosts;
+        app.swap_active_host();
+        app.view_mode = ViewMode::Cluster;
+        app
+    }
+
+    fn with_source(
+        topology: ZenTopology,
+        stats: SystemStats,
+        source: Option<Box<dyn StatsSource>>,
+        config: &Config,
+    ) -> Self {
+        let mut history = History::new(config.history);
+        if !stats.core_usages.is_empty() {
+            history.push(&stats, &topology);
+        }
+
+        // cgroup, IRQ and vCPU scans walk sysfs and /proc, so they run on
+        // their own threads and only while their data is on screen
+        let interval = Duration::from_millis(config.refresh_rate);
+        let cgroup_worker = Background::spawn(
+            || {
+                let mut collector = CgroupCollector::new();
+                move || collector.refresh()
+            },
+            interval,
+            false,
+        );
+        let vcpu_worker = Background::spawn(
+            || {
+                let scanner = VcpuScanner::new();
+                move || scanner.scan()
+            },
+            interval,
+            false,
+        );
+        let interrupt_worker = Background::spawn(
+            || {
+                let mut collector = InterruptCollector::new();
+                move || collector.refresh()
+            },
+            interval,
+            false,
+        );
+        let detail_topology = topology.clone();
+        let detail_worker = Background::spawn(
+            || {
+                let mut collector = DetailCollector::new(detail_topology);
+                move || collector.refresh()
+            },
+            interval,
+            false,
+        );
+
+        let mut app = Self {
             topology,
             stats,
+            history,
+            cgroups: Vec::new(),
+            guests: Vec::new(),
+            interrupts: InterruptStats::default(),
+            details: CpuDetails::default(),
             view_mode: ViewMode::Core,
-            display_mode: DisplayMode::default(),
-            show_smt: true,
+            display_mode: config.display,
+            show_smt: config.show_smt,
+            show_vcpus: false,
             show_help: false,
+            help_scroll: Scroll::default(),
             should_quit: false,
-            scroll_offset: 0,
-            collector,
-        }
+            scroll: [Scroll::default(); 2],
+            selected_cpu: 0,
+            selected_group: 0,
+            show_chart: false,
+            show_detail: false,
+            steal_threshold: config.steal_threshold,
+            selected_host: 0,
+            focused_socket: 0,
+            refresh_interval: interval,
+            frozen: false,
+            columns: config.columns,
+            theme: config.theme.clone(),
+            socket: config.socket,
+            cpus: config.cpus.clone(),
+            collectors: config.collectors,
+            settings: config.settings.clone(),
+            settings_path: config.settings_path.clone(),
+            notice: None,
+            active_host: 0,
+            source,
+            hosts: Vec::new(),
+            recorder: None,
+            sinks: None,
+            cgroup_worker,
+            vcpu_worker,
+            interrupt_worker,
+            detail_worker,
+        };
+        app.set_view_mode(config.view);
+        app.select_cpu(app.cursor_cpus().first().copied().unwrap_or(0));
+        app
     }
 
-    /// Cycle display mode: Full -> Compact -> Heatmap -> Full
+    /// Cycle display mode: Full -> Compact -> Heatmap -> Sparkline -> Waterfall -> Full
     pub fn cycle_display_mode(&mut self) {
         self.display_mode = match self.display_mode {
             DisplayMode::Full => DisplayMode::Compact,
             DisplayMode::Compact => DisplayMode::Heatmap,
-            DisplayMode::Heatmap => DisplayMode::Full,
+            DisplayMode::Heatmap => DisplayMode::Sparkline,
+            DisplayMode::Sparkline => DisplayMode::Waterfall,
+            DisplayMode::Waterfall => DisplayMode::Full,
+        };
+        self.scroll = [Scroll::default(); 2];
+    }
+
+    /// Apply snapshots and collector results that arrived since the last tick
+    ///
+    /// Never blocks: sampling happens on background threads.
+    pub fn refresh_stats(&mut self) -> Result<()> {
+        let mut samples = self
+            .source
+            .as_mut()
+            .map(|source| source.poll())
+            .unwrap_or_default();
+        for (idx, host) in self.hosts.iter_mut().enumerate() {
+            let polled = host.source.poll();
+            if idx == self.active_host {
+                samples = polled;
+            } else if !self.frozen {
+                host.push(polled);
+            }
+        }
+        for stats in samples {
+            self.push_stats(stats)?;
+        }
+
+        if self.frozen {
+            // Drop collector results so unfreezing starts from fresh ones
+            self.cgroup_worker.drain();
+            self.interrupt_worker.drain();
+            self.vcpu_worker.drain();
+            self.detail_worker.drain();
+            return Ok(());
+        }
+
+        if let Some(cgroups) = self.cgroup_worker.latest() {
+            self.cgroups = cgroups;
+        }
+        if let Some(interrupts) = self.interrupt_worker.latest() {
+            self.interrupts = interrupts;
+        }
+        if let Some(details) = self.detail_worker.latest() {
+            if self.show_detail {
+                self.details = details;
+            }
+        }
+        if let Some(guests) = self.vcpu_worker.latest() {
+            if self.show_vcpus {
+                self.guests = guests;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run the cgroup, IRQ, vCPU and detail workers only while their data is on screen
+    ///
+    /// They describe this host, so they stay idle for recordings and agents.
+    fn update_workers(&self) {
+        let live = self.is_live();
+        self.cgroup_worker
+            .set_active(live && self.view_mode == ViewMode::Cgroup);
+        self.interrupt_worker
+            .set_active(live && self.view_mode == ViewMode::Irq);
+        self.vcpu_worker.set_active(live && self.show_vcpus);
+        self.detail_worker
+            .set_active(live && self.show_detail && self.has_cursor());
+    }
+
+    /// Make a snapshot current, recording and exporting it if enabled
+    ///
+    /// While frozen, snapshots are still recorded and exported but not shown.
+    fn push_stats(&mut self, stats: SystemStats) -> Result<()> {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.write(&stats)?;
+        }
+        if let Some(sinks) = &self.sinks {
+            sinks.send(stats.clone());
+            // A failing sink backs off on its own; the session keeps going
+            for error in sinks.errors() {
+                self.set_notice(error);
+            }
+        }
+        if !self.frozen {
+            self.history.push(&stats, &self.topology);
+            self.stats = stats;
+        }
+        Ok(())
+    }
+
+    /// How often the event loop has to poll for snapshots; `None` when
+    /// every source wakes it instead
+    pub fn poll_interval(&self) -> Option<Duration> {
+        let wakes = self.source.as_ref().map_or(true, |source| source.wakes())
+            && self.hosts.iter().all(|host| host.source.wakes());
+        (!wakes).then_some(self.refresh_interval)
+    }
+
+    /// Whether snapshots are sampled from this host
+    pub fn is_live(&self) -> bool {
+        self.source.as_ref().is_some_and(|source| source.is_local())
+    }
+
+    /// Record every subsequent snapshot to a file
+    pub fn start_recording(&mut self, path: &Path) -> Result<()> {
+        if !self.hosts.is_empty() {
+            bail!("--record supports a single host");
+        }
+        self.recorder = Some(Recorder::create(path, &self.topology)?);
+        Ok(())
+    }
+
+    /// Push every subsequent snapshot to output sinks, on a background thread
+    pub fn add_sinks(&mut self, sinks: Vec<Box<dyn Sink>>) {
+        if !sinks.is_empty() {
+            self.sinks = Some(SinkWorker::spawn(self.topology.clone(), sinks));
+        }
+    }
+
+    /// Source status for the header (replay position, remote host or recording marker)
+    pub fn source_status(&self) -> Option<String> {
+        if let Some(host) = self.hosts.get(self.active_host) {
+            return Some(format!(
+                "CLUSTER {}/{} {}",
+                self.active_host + 1,
+                self.hosts.len(),
+                host.source.status().unwrap_or_default()
+            ));
+        }
+        let status = self.source.as_ref().and_then(|source| source.status());
+        match (status, &self.recorder) {
+            (Some(status), Some(_)) => Some(format!("{} REC", status)),
+            (None, Some(_)) => Some(String::from("REC")),
+            (status, None) => status,
+        }
+    }
+
+    /// Playback controls, if the source is a recording
+    fn playback(&mut self) -> Option<&mut dyn Playback> {
+        self.source.as_mut()?.playback()
+    }
+
+    /// Pause or resume replay, or freeze the live display
+    pub fn toggle_pause(&mut self) {
+        match self.playback() {
+            Some(playback) => playback.toggle_pause(),
+            None => self.frozen = !self.frozen,
+        }
+    }
+
+    /// Step replay forward by one sample
+    pub fn step(&mut self) -> Result<()> {
+        if let Some(stats) = self.playback().and_then(|playback| playback.step()) {
+            self.push_stats(stats)?;
+        }
+        Ok(())
+    }
+
+    /// Seek replay backward
+    pub fn seek_backward(&mut self) {
+        if let Some(playback) = self.playback() {
+            playback.seek_backward();
+        }
+        self.rebuild_history();
+    }
+
+    /// Seek replay forward
+    pub fn seek_forward(&mut self) {
+        if let Some(playback) = self.playback() {
+            playback.seek_forward();
+        }
+        self.rebuild_history();
+    }
+
+    /// Increase replay speed, or sample more often
+    pub fn faster(&mut self) {
+        match self.playback() {
+            Some(playback) => playback.faster(),
+            None => {
+                let current = self.refresh_interval.as_millis() as u64;
+                if let Some(&ms) = REFRESH_STEPS_MS.iter().rev().find(|&&ms| ms < current) {
+                    self.set_refresh_interval(Duration::from_millis(ms));
+                }
+            }
+        }
+    }
+
+    /// Decrease replay speed, or sample less often
+    pub fn slower(&mut self) {
+        match self.playback() {
+            Some(playback) => playback.slower(),
+            None => {
+                let current = self.refresh_interval.as_millis() as u64;
+                if let Some(&ms) = REFRESH_STEPS_MS.iter().find(|&&ms| ms > current) {
+                    self.set_refresh_interval(Duration::from_millis(ms));
+                }
+            }
+        }
+    }
+
+    /// Change the sampling period of the source and background collectors
+    fn set_refresh_interval(&mut self, interval: Duration) {
+        self.refresh_interval = interval;
+        if let Some(source) = &mut self.source {
+            source.set_interval(interval);
+        }
+        for host in &mut self.hosts {
+            host.source.set_interval(interval);
+        }
+        self.cgroup_worker.set_interval(interval);
+        self.interrupt_worker.set_interval(interval);
+        self.vcpu_worker.set_interval(interval);
+        self.detail_worker.set_interval(interval);
+    }
+
+    /// Refill history from the recording after a seek
+    fn rebuild_history(&mut self) {
+        let depth = self.history.depth();
+        let Some(playback) = self.playback() else {
+            return;
         };
-        self.scroll_offset = 0;
+        let window = playback.window(depth);
+        self.history.clear();
+        for stats in &window {
+            self.history.push(stats, &self.topology);
+        }
+        if let Some(stats) = window.into_iter().last() {
+            self.stats = stats;
+        }
     }
 
-    /// Refresh CPU statistics
-    pub fn refresh_stats(&mut self) {
-        self.stats = self.collector.refresh();
+    /// Exchange the active host's data with the `App` fields
+    fn swap_active_host(&mut self) {
+        if let Some(host) = self.hosts.get_mut(self.active_host) {
+            mem::swap(&mut self.topology, &mut host.topology);
+            mem::swap(&mut self.stats, &mut host.stats);
+            mem::swap(&mut self.history, &mut host.history);
+            if let Some(sinks) = &self.sinks {
+                sinks.set_topology(self.topology.clone());
+            }
+        }
+    }
+
+    /// Per-host data for the cluster overview, in connection order
+    pub fn cluster_hosts(&self) -> Vec<HostView<'_>> {
+        self.hosts
+            .iter()
+            .enumerate()
+            .map(|(idx, host)| {
+                let (topology, stats) = if idx == self.active_host {
+                    (&self.topology, &self.stats)
+                } else {
+                    (&host.topology, &host.stats)
+                };
+                HostView {
+                    name: host.source.addr(),
+                    connected: host.source.is_connected(),
+                    topology,
+                    stats,
+                }
+            })
+            .collect()
+    }
+
+    /// Drill into the host selected in the cluster overview
+    pub fn focus_selected_host(&mut self) {
+        if self.selected_host >= self.hosts.len() {
+            return;
+        }
+        if self.selected_host != self.active_host {
+            self.swap_active_host();
+            self.active_host = self.selected_host;
+            self.swap_active_host();
+            self.selected_cpu = self.topology.cores.first().map_or(0, |c| c.id);
+        }
+        self.set_view_mode(ViewMode::Core);
+        self.keep_selection_visible();
     }
 
     /// Set view mode
     pub fn set_view_mode(&mut self, mode: ViewMode) {
+        let available = match mode {
+            // The overview needs more than one host
+            ViewMode::Cluster => !self.hosts.is_empty(),
+            // cgroups and interrupts are read from this host, which a
+            // recording or agent does not describe
+            ViewMode::Cgroup => self.collectors.cgroup && self.is_live(),
+            ViewMode::Irq => self.collectors.irq && self.is_live(),
+            _ => true,
+        };
+        if !available {
+            return;
+        }
         if self.view_mode != mode {
             self.view_mode = mode;
-            self.scroll_offset = 0; // Reset scroll when changing view
+            self.scroll = [Scroll::default(); 2]; // Reset scroll when changing view
+            self.selected_group = 0;
+            // Grouped views select the group holding the selected CPU
+            self.select_cpu(self.selected_cpu);
+            self.update_workers();
+        }
+    }
+
+    /// Switch to the next available view: Core -> CCD -> NPS -> cgroup -> IRQ -> Cluster
+    pub fn cycle_view_mode(&mut self) {
+        const ORDER: [ViewMode; 6] = [
+            ViewMode::Core,
+            ViewMode::Ccd,
+            ViewMode::Nps,
+            ViewMode::Cgroup,
+            ViewMode::Irq,
+            ViewMode::Cluster,
+        ];
+        let current = ORDER.iter().position(|&m| m == self.view_mode).unwrap_or(0);
+        // set_view_mode ignores unavailable views, so keep going until one sticks
+        for step in 1..ORDER.len() {
+            self.set_view_mode(ORDER[(current + step) % ORDER.len()]);
+            if self.view_mode != ORDER[current] {
+                break;
+            }
         }
     }
 
+    /// Give a socket pane of the split view focus
+    pub fn focus_socket(&mut self, socket: usize) {
+        self.focused_socket = socket;
+    }
+
     /// Toggle SMT display
     pub fn toggle_smt(&mut self) {
         self.show_smt = !self.show_smt;
-        self.scroll_offset = 0;
+        self.scroll = [Scroll::default(); 2];
+        self.keep_selection_visible();
+    }
+
+    /// Aggregate steal percentage, if it exceeds the warning threshold
+    pub fn steal_warning(&self) -> Option<f32> {
+        (self.stats.total_steal > self.steal_threshold).then_some(self.stats.total_steal)
+    }
+
+    /// Toggle guest vCPU placement overlay
+    pub fn toggle_vcpus(&mut self) {
+        if !self.collectors.vcpu || !self.is_live() {
+            return;
+        }
+        self.show_vcpus = !self.show_vcpus;
+        if !self.show_vcpus {
+            self.guests.clear();
+        }
+        self.update_workers();
+    }
+
+    /// Guest/vCPU labels keyed by the host CPU they last ran on
+    pub fn vcpu_labels(&self) -> HashMap<usize, String> {
+        let mut labels: HashMap<usize, String> = HashMap::new();
+        for guest in &self.guests {
+            for vcpu in &guest.vcpus {
+                let label = format!("{}/{}", guest.name, vcpu.vcpu);
+                labels
+                    .entry(vcpu.cpu)
+                    .and_modify(|l| {
+                        l.push(',');
+                        l.push_str(&label);
+                    })
+                    .or_insert(label);
+            }
+        }
+        labels
     }
 
     /// Toggle help overlay
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
+        self.help_scroll.offset = 0;
+    }
+
+    /// Scroll the help overlay by `lines`, stopping at either end
+    pub fn scroll_help(&mut self, lines: isize) {
+        let scroll = &mut self.help_scroll;
+        scroll.offset = scroll
+            .offset
+            .saturating_add_signed(lines)
+            .min(scroll.max_offset());
+    }
+
+    /// Move the selection by `steps`: hosts in the cluster overview, groups
+    /// in grouped sparkline and waterfall modes, otherwise CPUs in the order
+    /// the view draws them
+    pub fn move_cursor(&mut self, steps: isize) {
+        let step =
+            |pos: usize, len: usize| pos.saturating_add_signed(steps).min(len.saturating_sub(1));
+        if self.view_mode == ViewMode::Cluster {
+            self.selected_host = step(self.selected_host, self.hosts.len());
+            return;
+        }
+        if self.cursor_on_groups() {
+            let groups = self.visible_group_ids();
+            if let Some(&group_id) = groups.get(step(self.selected_group, groups.len())) {
+                self.select_group(group_id);
+            }
+            return;
+        }
+        let cpus = self.cursor_cpus();
+        let pos = cpus.iter().position(|&id| id == self.selected_cpu).unwrap_or(0);
+        if let Some(&cpu_id) = cpus.get(step(pos, cpus.len())) {
+            self.select_cpu(cpu_id);
+        }
+    }
+
+    /// Select a host of the cluster overview by its position
+    pub fn select_host(&mut self, idx: usize) {
+        if idx < self.hosts.len() {
+            self.selected_host = idx;
+        }
+    }
+
+    /// Whether the current view has a cursor; other views scroll instead
+    pub fn has_cursor(&self) -> bool {
+        matches!(self.view_mode, ViewMode::Core | ViewMode::Ccd | ViewMode::Nps)
+    }
+
+    /// Whether the cursor moves over groups because the view draws no CPUs
+    pub fn cursor_on_groups(&self) -> bool {
+        self.group_kind().is_some()
+            && matches!(self.display_mode, DisplayMode::Sparkline | DisplayMode::Waterfall)
+    }
+
+    /// Select a CPU; grouped views also select the group containing it
+    pub fn select_cpu(&mut self, cpu_id: usize) {
+        self.selected_cpu = cpu_id;
+        let group_id = match self.group_kind() {
+            Some(GroupKind::Ccd) => self.core(cpu_id).map(|c| c.ccd_id),
+            Some(GroupKind::Nps) => self
+                .topology
+                .nps_nodes
+                .iter()
+                .find(|n| n.cores.contains(&cpu_id))
+                .map(|n| n.id),
+            None => None,
+        };
+        if let Some(pos) =
+            group_id.and_then(|id| self.visible_group_ids().iter().position(|&g| g == id))
+        {
+            self.selected_group = pos;
+        }
+    }
+
+    /// Topology entry of a CPU
+    pub fn core(&self, cpu_id: usize) -> Option<&CpuCore> {
+        self.topology.core(cpu_id)
+    }
+
+    /// Select a CCD or NPS node by ID in grouped views, and its first CPU
+    /// unless the selected CPU already belongs to it
+    pub fn select_group(&mut self, group_id: usize) {
+        let Some(pos) = self.visible_group_ids().iter().position(|&id| id == group_id) else {
+            return;
+        };
+        self.selected_group = pos;
+        let cpus = self.group_cpus(group_id);
+        if !cpus.contains(&self.selected_cpu) {
+            if let Some(&cpu_id) = cpus.first() {
+                self.selected_cpu = cpu_id;
+            }
+        }
+    }
+
+    /// Move the selection off a CPU the views no longer show: to its SMT
+    /// sibling if that is shown, otherwise to the first CPU shown
+    fn keep_selection_visible(&mut self) {
+        let cpus = self.cursor_cpus();
+        if cpus.contains(&self.selected_cpu) {
+            return;
+        }
+        let sibling = self
+            .core(self.selected_cpu)
+            .and_then(|c| c.smt_sibling)
+            .filter(|id| cpus.contains(id));
+        if let Some(&cpu_id) = sibling.as_ref().or(cpus.first()) {
+            self.select_cpu(cpu_id);
+        }
+    }
+
+    /// Group kind shown by the current view, if it is a grouped view
+    pub fn group_kind(&self) -> Option<GroupKind> {
+        match self.view_mode {
+            ViewMode::Ccd => Some(GroupKind::Ccd),
+            ViewMode::Nps => Some(GroupKind::Nps),
+            _ => None,
+        }
+    }
+
+    /// ID of the selected CCD or NPS node in grouped views
+    pub fn selected_group_id(&self) -> Option<usize> {
+        self.visible_group_ids().get(self.selected_group).copied()
+    }
+
+    /// Reject `--socket`/`--cpus` filters that match no CPU of this topology
+    pub fn check_filters(&self) -> Result<()> {
+        if let Some(socket) = self.socket {
+            if !self.topology.cores.iter().any(|c| c.package_id == socket) {
+                bail!("Socket {} not found ({} present)", socket, self.topology.packages);
+            }
+        }
+        if self.visible_cpus().is_empty() {
+            bail!("No CPUs match --socket/--cpus");
+        }
+        Ok(())
+    }
+
+    /// Whether `--socket` and `--cpus` let the views show this CPU
+    fn shows_cpu(&self, core: &CpuCore) -> bool {
+        self.socket.iter().all(|&s| core.package_id == s)
+            && self.cpus.iter().all(|cpus| cpus.contains(&core.id))
+    }
+
+    /// Whether the views currently draw this CPU, given the filters and SMT setting
+    fn cursor_shows(&self, core: &CpuCore) -> bool {
+        self.shows_cpu(core) && (self.show_smt || core.smt_sibling.iter().all(|&s| core.id < s))
+    }
+
+    /// CPUs the core view can show, SMT siblings included
+    fn visible_cpus(&self) -> Vec<&CpuCore> {
+        self.topology.cores.iter().filter(|c| self.shows_cpu(c)).collect()
+    }
+
+    /// CPUs the cursor steps through, in the order the current view draws them
+    fn cursor_cpus(&self) -> Vec<usize> {
+        if self.group_kind().is_some() {
+            return self
+                .visible_group_ids()
+                .into_iter()
+                .flat_map(|group_id| self.group_cpus(group_id))
+                .collect();
+        }
+        let mut cpus: Vec<&CpuCore> = self
+            .topology
+            .cores
+            .iter()
+            .filter(|c| self.cursor_shows(c))
+            .collect();
+        // The split view draws each socket in its own pane
+        if self.topology.packages == 2 {
+            cpus.sort_by_key(|c| c.package_id);
+        }
+        cpus.into_iter().map(|c| c.id).collect()
+    }
+
+    /// Shown CPUs of a CCD or NPS node, in the order the grouped view draws them
+    fn group_cpus(&self, group_id: usize) -> Vec<usize> {
+        let members = match self.group_kind() {
+            Some(GroupKind::Ccd) => self
+                .topology
+                .ccds
+                .iter()
+                .find(|c| c.id == group_id)
+                .map(|c| &c.cores),
+            Some(GroupKind::Nps) => self
+                .topology
+                .nps_nodes
+                .iter()
+                .find(|n| n.id == group_id)
+                .map(|n| &n.cores),
+            None => None,
+        };
+        members
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&cpu_id| self.core(cpu_id).is_some_and(|c| self.cursor_shows(c)))
+            .collect()
+    }
+
+    /// IDs of the groups the current grouped view shows, in display order
+    fn visible_group_ids(&self) -> Vec<usize> {
+        let shown = |cpus: &[usize]| {
+            cpus.iter()
+                .filter_map(|&cpu_id| self.core(cpu_id))
+                .any(|c| self.cursor_shows(c))
+        };
+        match self.group_kind() {
+            Some(GroupKind::Ccd) => self
+                .topology
+                .ccds
+                .iter()
+                .filter(|c| shown(&c.cores))
+                .map(|c| c.id)
+                .collect(),
+            Some(GroupKind::Nps) => self
+                .topology
+                .nps_nodes
+                .iter()
+                .filter(|n| shown(&n.cores))
+                .map(|n| n.id)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Toggle the history chart for the selected CPU or group
+    pub fn toggle_chart(&mut self) {
+        self.show_chart = !self.show_chart;
+    }
+
+    /// Toggle the detail panel for the selected CPU
+    pub fn toggle_detail(&mut self) {
+        self.show_detail = !self.show_detail;
+        if !self.show_detail {
+            // The worker's first result after idling only primes it
+            self.details = CpuDetails::default();
+        }
+        self.update_workers();
+    }
+
+    /// Pane that keyboard and wheel scrolling act on: the focused socket pane
+    /// of a split view, otherwise the only pane
+    pub fn scroll_pane(&self) -> usize {
+        let split = self.socket.is_none()
+            && self.topology.packages == 2
+            && !matches!(
+                self.view_mode,
+                ViewMode::Cgroup | ViewMode::Irq | ViewMode::Cluster
+            );
+        if split {
+            self.focused_socket.min(1)
+        } else {
+            0
+        }
+    }
+
+    /// Scroll the active pane by `lines`, stopping at either end of its content
+    pub fn scroll_by(&mut self, lines: isize) {
+        let scroll = &mut self.scroll[self.scroll_pane()];
+        scroll.offset = scroll
+            .offset
+            .saturating_add_signed(lines)
+            .min(scroll.max_offset());
     }
 
     /// Scroll up
     pub fn scroll_up(&mut self) {
-        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+        self.scroll_by(-1);
     }
 
     /// Scroll down
     pub fn scroll_down(&mut self) {
-        self.scroll_offset = self.scroll_offset.saturating_add(1);
+        self.scroll_by(1);
+    }
+
+    /// Lines a page up or down moves in the active pane
+    pub fn page_size(&self) -> usize {
+        self.scroll[self.scroll_pane()].viewport.max(1)
+    }
+
+    /// Scroll a pane to the top or bottom of its content
+    pub fn scroll_to_edge(&mut self, pane: usize, end: bool) {
+        let scroll = &mut self.scroll[pane];
+        scroll.offset = if end { scroll.max_offset() } else { 0 };
+    }
+
+    /// Scroll a pane just far enough that content lines `line..line + height` are visible
+    pub fn scroll_into_view(&mut self, pane: usize, line: usize, height: usize, viewport: usize) {
+        let scroll = &mut self.scroll[pane];
+        if line < scroll.offset {
+            scroll.offset = line;
+        } else if line + height > scroll.offset + viewport {
+            scroll.offset = (line + height).saturating_sub(viewport);
+        }
+    }
+
+    /// Write the current view, display mode, SMT, theme and refresh settings to the config file
+    pub fn save_settings(&mut self) {
+        let Some(path) = self.settings_path.clone() else {
+            self.set_notice(String::from("No config path (set $HOME or --config)"));
+            return;
+        };
+
+        let mut settings = self.settings.clone();
+        settings.refresh_rate = Some(self.refresh_interval.as_millis() as u64);
+        if self.view_mode != ViewMode::Cluster {
+            settings.view = Some(self.view_mode);
+        }
+        settings.display = Some(self.display_mode);
+        settings.show_smt = Some(self.show_smt);
+        settings.theme = Some(self.theme.clone());
+
+        match settings.save(&path) {
+            Ok(()) => {
+                self.settings = settings;
+                self.set_notice(format!("Saved {}", path.display()));
+            }
+            Err(e) => self.set_notice(format!("{:#}", e)),
+        }
+    }
+
+    /// Show a short-lived message in the header
+    pub fn set_notice(&mut self, message: String) {
+        self.notice = Some((message, Instant::now()));
+    }
+
+    /// Recent notice for the header, if any
+    pub fn notice(&self) -> Option<&str> {
+        self.notice
+            .as_ref()
+            .filter(|(_, shown)| shown.elapsed() < NOTICE_DURATION)
+            .map(|(message, _)| message.as_str())
     }
 
     /// Request quit
@@ -0,0 +1,306 @@
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::config::{BatchFormat, Config, Granularity};
+use crate::stats::{sampler, CoreUsage, GroupUsage, SystemStats};
+use crate::topology::{format_cpu_list, ZenTopology};
+
+/// One output row per logical CPU
+#[derive(Serialize)]
+struct CpuRow {
+    timestamp_ms: u64,
+    sample: u64,
+    cpu: usize,
+    core: usize,
+    smt_thread: usize,
+    ccx: usize,
+    ccd: usize,
+    numa: usize,
+    package: usize,
+    usage: f32,
+    steal: f32,
+    guest: f32,
+    freq_mhz: Option<u64>,
+}
+
+/// One output row per CCD or NPS node
+#[derive(Serialize)]
+struct GroupRow {
+    timestamp_ms: u64,
+    sample: u64,
+    group: &'static str,
+    id: usize,
+    package: Option<usize>,
+    numa: String,
+    cpus: String,
+    core_count: usize,
+    usage: f32,
+    min_usage: f32,
+    max_usage: f32,
+    steal: f32,
+    guest: f32,
+    freq_mhz: Option<u64>,
+}
+
+/// Row types that can also be written as CSV
+trait Row: Serialize {
+    const HEADER: &'static [&'static str];
+
+    fn values(&self) -> Vec<String>;
+}
+
+impl Row for CpuRow {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp_ms",
+        "sample",
+        "cpu",
+        "core",
+        "smt_thread",
+        "ccx",
+        "ccd",
+        "numa",
+        "package",
+        "usage",
+        "steal",
+        "guest",
+        "freq_mhz",
+    ];
+
+    fn values(&self) -> Vec<String> {
+        vec![
+            self.timestamp_ms.to_string(),
+            self.sample.to_string(),
+            self.cpu.to_string(),
+            self.core.to_string(),
+            self.smt_thread.to_string(),
+            self.ccx.to_string(),
+            self.ccd.to_string(),
+            self.numa.to_string(),
+            self.package.to_string(),
+            format!("{:.2}", self.usage),
+            format!("{:.2}", self.steal),
+            format!("{:.2}", self.guest),
+            optional(self.freq_mhz),
+        ]
+    }
+}
+
+impl Row for GroupRow {
+    const HEADER: &'static [&'static str] = &[
+        "timestamp_ms",
+        "sample",
+        "group",
+        "id",
+        "package",
+        "numa",
+        "cpus",
+        "core_count",
+        "usage",
+        "min_usage",
+        "max_usage",
+        "steal",
+        "guest",
+        "freq_mhz",
+    ];
+
+    fn values(&self) -> Vec<String> {
+        vec![
+            self.timestamp_ms.to_string(),
+            self.sample.to_string(),
+            self.group.to_string(),
+            self.id.to_string(),
+            optional(self.package),
+            csv_field(&self.numa),
+            csv_field(&self.cpus),
+            self.core_count.to_string(),
+            format!("{:.2}", self.usage),
+            format!("{:.2}", self.min_usage),
+            format!("{:.2}", self.max_usage),
+            format!("{:.2}", self.steal),
+            format!("{:.2}", self.guest),
+            optional(self.freq_mhz),
+        ]
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Quote a field when it contains separators (cpu lists do)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Sample without the TUI and write rows to stdout, like `top -b`
+///
+/// Runs until `--iterations` samples have been written, or forever.
+pub fn run(topology: &ZenTopology, config: &Config) -> Result<()> {
+    let mut out = BufWriter::new(io::stdout().lock());
+    match write_samples(&mut out, topology, config) {
+        // A closed pipe (e.g. `| head`) is a normal way to stop
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn write_samples<W: Write>(out: &mut W, topology: &ZenTopology, config: &Config) -> io::Result<()> {
+    let mut sampler = sampler(config.source, topology);
+    let interval = Duration::from_millis(config.refresh_rate);
+
+    if config.format == BatchFormat::Csv {
+        let header = match config.granularity {
+            Granularity::Cpu => CpuRow::HEADER,
+            Granularity::Ccd | Granularity::Nps => GroupRow::HEADER,
+        };
+        writeln!(out, "{}", header.join(","))?;
+    }
+
+    let mut sample = 0;
+    loop {
+        let stats = sampler.sample();
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        match config.granularity {
+            Granularity::Cpu => {
+                for row in cpu_rows(topology, &stats, timestamp_ms, sample) {
+                    write_row(out, &row, config.format)?;
+                }
+            }
+            Granularity::Ccd | Granularity::Nps => {
+                for row in group_rows(topology, &stats, config.granularity, timestamp_ms, sample) {
+                    write_row(out, &row, config.format)?;
+                }
+            }
+        }
+        out.flush()?;
+
+        sample += 1;
+        if config.iterations.is_some_and(|n| sample >= n) {
+            return Ok(());
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+fn write_row<W: Write, R: Row>(out: &mut W, row: &R, format: BatchFormat) -> io::Result<()> {
+    match format {
+        BatchFormat::Csv => writeln!(out, "{}", row.values().join(",")),
+        BatchFormat::Jsonl => {
+            serde_json::to_writer(&mut *out, row)?;
+            writeln!(out)
+        }
+    }
+}
+
+fn cpu_rows(
+    topology: &ZenTopology,
+    stats: &SystemStats,
+    timestamp_ms: u64,
+    sample: u64,
+) -> Vec<CpuRow> {
+    topology
+        .cores
+        .iter()
+        .filter_map(|core| {
+            let usage = stats.usage(core.id)?;
+            Some(CpuRow {
+                timestamp_ms,
+                sample,
+                cpu: core.id,
+                core: core.physical_id,
+                smt_thread: usize::from(core.smt_sibling.is_some_and(|s| s < core.id)),
+                ccx: core.ccx_id,
+                ccd: core.ccd_id,
+                numa: core.numa_node,
+                package: core.package_id,
+                usage: usage.usage_percent,
+                steal: usage.steal_percent,
+                guest: usage.guest_percent,
+                freq_mhz: usage.frequency_mhz,
+            })
+        })
+        .collect()
+}
+
+fn group_rows(
+    topology: &ZenTopology,
+    stats: &SystemStats,
+    granularity: Granularity,
+    timestamp_ms: u64,
+    sample: u64,
+) -> Vec<GroupRow> {
+    let (group, members): (&'static str, Vec<(usize, &[usize])>) = match granularity {
+        Granularity::Nps => (
+            "nps",
+            topology
+                .nps_nodes
+                .iter()
+                .map(|n| (n.id, n.cores.as_slice()))
+                .collect(),
+        ),
+        _ => (
+            "ccd",
+            topology
+                .ccds
+                .iter()
+                .map(|c| (c.id, c.cores.as_slice()))
+                .collect(),
+        ),
+    };
+
+    members
+        .into_iter()
+        .map(|(id, cpus)| {
+            let usages: Vec<CoreUsage> = cpus
+                .iter()
+                .filter_map(|&cpu_id| stats.usage(cpu_id).cloned())
+                .collect();
+            let usage = GroupUsage::from_cores(String::new(), id, &usages);
+            let average = |f: fn(&CoreUsage) -> f32| {
+                if usages.is_empty() {
+                    0.0
+                } else {
+                    usages.iter().map(f).sum::<f32>() / usages.len() as f32
+                }
+            };
+            let frequencies: Vec<u64> = usages.iter().filter_map(|u| u.frequency_mhz).collect();
+            let numa: Vec<String> = topology
+                .numa_nodes_for_cpus(cpus)
+                .iter()
+                .map(|n| n.to_string())
+                .collect();
+
+            GroupRow {
+                timestamp_ms,
+                sample,
+                group,
+                id,
+                package: cpus
+                    .first()
+                    .and_then(|&cpu_id| topology.core(cpu_id))
+                    .map(|c| c.package_id),
+                numa: numa.join(","),
+                cpus: format_cpu_list(cpus),
+                core_count: usage.core_count,
+                usage: usage.usage_percent,
+                min_usage: usage.min_usage,
+                max_usage: usage.max_usage,
+                steal: average(|u| u.steal_percent),
+                guest: average(|u| u.guest_percent),
+                freq_mhz: (!frequencies.is_empty())
+                    .then(|| frequencies.iter().sum::<u64>() / frequencies.len() as u64),
+            }
+        })
+        .collect()
+}
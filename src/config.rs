@@ -1,22 +1,447 @@
-use clap::Parser;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use clap::parser::ValueSource;
+use clap::{ArgAction, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{DisplayMode, ViewMode};
+use crate::db::query::Aggregate;
+use crate::settings::Settings;
+use crate::topology::{parse_cpu_list, MAX_CPUS};
+use crate::ui::gradient::Gradient;
 
 /// zentop - AMD Zen CPU Monitor
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Config {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Configuration file (default: $XDG_CONFIG_HOME/zentop/config.toml)
+    #[arg(long, value_name = "FILE", global = true)]
+    pub config: Option<PathBuf>,
+
     /// Refresh rate in milliseconds
-    #[arg(short, long, default_value_t = 1000)]
+    #[arg(short, long, default_value_t = 1000, value_parser = parse_refresh_rate, global = true)]
     pub refresh_rate: u64,
+
+    /// Where live samples come from
+    #[arg(long, value_enum, default_value_t = SourceKind::Sysinfo, global = true)]
+    pub source: SourceKind,
+
+    /// Warn in the header when aggregate steal time exceeds this percentage
+    #[arg(long, default_value_t = 10.0, value_name = "PCT")]
+    pub steal_threshold: f32,
+
+    /// Number of samples kept for sparklines and history charts
+    #[arg(long, default_value_t = 300, value_name = "SAMPLES")]
+    pub history: usize,
+
+    /// Record every sample to a file for later replay
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Write samples to stdout instead of starting the TUI
+    #[arg(short, long)]
+    pub batch: bool,
+
+    /// Batch output format
+    #[arg(long, value_enum, default_value_t = BatchFormat::Csv)]
+    pub format: BatchFormat,
+
+    /// Batch rows per CPU, per CCD or per NPS node
+    #[arg(long, value_enum, default_value_t = Granularity::Cpu)]
+    pub granularity: Granularity,
+
+    /// Number of batch samples to write before exiting (default: unlimited)
+    #[arg(short = 'n', long, value_name = "N", value_parser = parse_iterations)]
+    pub iterations: Option<u64>,
+
+    /// Write InfluxDB line protocol to a file or udp://HOST:PORT
+    #[arg(long, value_name = "FILE|udp://HOST:PORT", conflicts_with = "batch")]
+    pub influx: Option<String>,
+
+    /// Send StatsD gauges to HOST:PORT over UDP
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "batch")]
+    pub statsd: Option<String>,
+
+    /// Persist samples to a SQLite history database
+    #[arg(long, value_name = "FILE", conflicts_with = "batch")]
+    pub db: Option<PathBuf>,
+
+    /// Delete database samples older than this (e.g. 12h, 7d)
+    #[arg(long, default_value = "7d", value_name = "DURATION", value_parser = parse_duration)]
+    pub db_retention: Duration,
+
+    /// Feed --influx/--statsd/--db without starting the TUI
+    #[arg(long, conflicts_with = "batch")]
+    pub no_tui: bool,
+
+    /// Leave the mouse to the terminal (text selection) instead of clicks and wheel scrolling
+    #[arg(long = "no-mouse", action = ArgAction::SetFalse, global = true)]
+    pub mouse: bool,
+
+    /// Initial view mode
+    #[arg(long, value_enum, default_value_t = ViewMode::Core, global = true)]
+    pub view: ViewMode,
+
+    /// Initial display mode
+    #[arg(long, value_enum, default_value_t = DisplayMode::Full, global = true)]
+    pub display: DisplayMode,
+
+    /// Start with SMT siblings hidden (physical cores only)
+    #[arg(long = "no-smt", action = ArgAction::SetFalse, global = true)]
+    pub show_smt: bool,
+
+    /// Only show CPUs of this socket
+    #[arg(long, value_name = "N", global = true)]
+    pub socket: Option<usize>,
+
+    /// Only show these CPUs (e.g. 0-15,64-79)
+    #[arg(long, value_name = "CPULIST", value_parser = parse_cpus, global = true)]
+    // Fully qualified so clap parses one list instead of repeated values
+    pub cpus: Option<::std::vec::Vec<usize>>,
+
+    /// Color theme: a built-in name, a theme file, or a file name in the themes directory
+    #[arg(long, default_value = "default", value_name = "NAME|FILE", global = true)]
+    pub theme: String,
+
+    /// Continuous color scale for heatmaps, group headers and waterfalls (default: theme's)
+    #[arg(long, value_enum, global = true)]
+    pub gradient: Option<Gradient>,
+
+    /// Usage percentages where bars switch color, overriding the theme
+    #[arg(skip)]
+    pub usage_thresholds: Option<[f32; 3]>,
+
+    /// Collectors the TUI may run
+    #[arg(skip)]
+    pub collectors: Collectors,
+
+    /// Optional columns of full-mode CPU bars
+    #[arg(skip)]
+    pub columns: Columns,
+
+    /// Where the "save settings" key writes
+    #[arg(skip)]
+    pub settings_path: Option<PathBuf>,
+
+    /// Contents of the config file, preserved when saving
+    #[arg(skip)]
+    pub settings: Settings,
+}
+
+/// `[collectors]` table: collectors the TUI may run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Collectors {
+    /// cgroup view
+    pub cgroup: bool,
+    /// IRQ view
+    pub irq: bool,
+    /// Guest vCPU placement overlay
+    pub vcpu: bool,
+}
+
+impl Default for Collectors {
+    fn default() -> Self {
+        Self {
+            cgroup: true,
+            irq: true,
+            vcpu: true,
+        }
+    }
+}
+
+/// `[columns]` table: optional columns of full-mode CPU bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Columns {
+    /// Usage percentage
+    pub percent: bool,
+    /// Steal and guest time
+    pub virt: bool,
+    /// Current frequency
+    pub frequency: bool,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Self {
+            percent: true,
+            virt: true,
+            frequency: false,
+        }
+    }
+}
+
+/// Live sample collector
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    /// sysinfo, with steal and guest time from /proc/stat
+    Sysinfo,
+    /// /proc/stat and cpufreq only
+    Procstat,
+    /// Generated load, for demos and testing views
+    Synthetic,
+}
+
+/// Batch output format
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchFormat {
+    /// Comma-separated values with a header line
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+}
+
+/// Grouping for `zentop query`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryGroup {
+    Cpu,
+    Ccd,
+    Numa,
+    Socket,
+}
+
+/// Parse durations like "90s", "30m", "2h" or "7d" (bare numbers are seconds)
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => s.split_at(idx),
+        None => (s, "s"),
+    };
+    let value: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", s))?;
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("invalid duration unit in '{}' (s, m, h or d)", s)),
+    };
+    let secs = value
+        .checked_mul(scale)
+        .ok_or_else(|| format!("duration '{}' is too long", s))?;
+    Ok(Duration::from_secs(secs))
+}
+
+/// Parse `--refresh-rate`; a zero interval would sample in a busy loop
+fn parse_refresh_rate(s: &str) -> Result<u64, String> {
+    match s.parse::<u64>() {
+        Ok(0) => Err(String::from("refresh rate must be at least 1 ms")),
+        Ok(ms) => Ok(ms),
+        Err(_) => Err(format!("invalid refresh rate '{}'", s)),
+    }
+}
+
+/// Parse `--iterations`; zero samples is almost certainly a mistake
+fn parse_iterations(s: &str) -> Result<u64, String> {
+    match s.parse::<u64>() {
+        Ok(0) => Err(String::from("iterations must be at least 1")),
+        Ok(n) => Ok(n),
+        Err(_) => Err(format!("invalid iteration count '{}'", s)),
+    }
+}
+
+/// Parse a `--cpus` list; unlike sysfs lists an empty result or an
+/// out-of-range ID is an error
+fn parse_cpus(s: &str) -> Result<Vec<usize>, String> {
+    let too_large = s
+        .split([',', '-'])
+        .filter_map(|n| n.trim().parse::<usize>().ok())
+        .find(|&cpu| cpu >= MAX_CPUS);
+    if let Some(cpu) = too_large {
+        return Err(format!("CPU {} is out of range (at most {})", cpu, MAX_CPUS - 1));
+    }
+    let cpus = parse_cpu_list(s);
+    if cpus.is_empty() {
+        return Err(format!("invalid CPU list '{}' (e.g. 0-7,16)", s));
+    }
+    Ok(cpus)
+}
+
+/// Batch row granularity
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Cpu,
+    Ccd,
+    Nps,
+}
+
+/// Non-interactive subcommands
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Propose a NUMA/CCD-local IRQ affinity layout for PCI devices
+    IrqPlan {
+        /// Write the proposed affinities to /proc/irq/*/smp_affinity_list
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Print the detected CPU topology and exit
+    Topo {
+        /// Print as JSON instead of a tree
+        #[arg(long)]
+        json: bool,
+    },
+    /// Serve Prometheus metrics over HTTP
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:9864", value_name = "ADDR")]
+        listen: SocketAddr,
+    },
+    /// Stream topology and stats to `zentop connect` clients
+    Agent {
+        /// Address to listen on; clients are not authenticated, so listen on
+        /// other interfaces (e.g. 0.0.0.0:9865) only on a trusted network
+        #[arg(long, default_value = "127.0.0.1:9865", value_name = "ADDR")]
+        listen: SocketAddr,
+    },
+    /// Run the TUI against one or more remote `zentop agent`s
+    Connect {
+        /// Agent addresses (host:port); more than one opens the cluster overview
+        #[arg(required = true, value_name = "HOST:PORT")]
+        addrs: Vec<String>,
+    },
+    /// Report historical usage aggregates from a --db database
+    Query {
+        /// Database written with --db
+        #[arg(long, value_name = "FILE")]
+        db: PathBuf,
+        /// How far back to look (e.g. 30m, 2h, 7d)
+        #[arg(long, default_value = "1h", value_name = "DURATION", value_parser = parse_duration)]
+        since: Duration,
+        /// Report per CPU, CCD, NUMA node or socket
+        #[arg(long, value_enum, default_value_t = QueryGroup::Ccd)]
+        group: QueryGroup,
+        /// Aggregate: avg, min, max or a percentile such as p95
+        #[arg(long, default_value = "avg", value_name = "AGG")]
+        agg: Aggregate,
+    },
+    /// Replay a recording in the TUI
+    Replay {
+        /// Recording written with --record
+        file: PathBuf,
+    },
 }
 
 impl Config {
-    pub fn parse_args() -> Self {
-        Self::parse()
+    /// Parse the command line and merge in the config file
+    pub fn parse_args() -> Result<Self> {
+        let matches = Self::command().get_matches();
+        let mut config = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+        // A missing default file is fine; a missing explicit one is a typo
+        let path = match &config.config {
+            Some(path) if !path.exists() => bail!("Config file {} not found", path.display()),
+            Some(path) => Some(path.clone()),
+            None => Settings::default_path(),
+        };
+        if let Some(path) = path {
+            let settings = Settings::load(&path)?;
+            settings.apply(&mut config, &matches);
+            config.settings = settings;
+            config.settings_path = Some(path);
+        }
+
+        // https://no-color.org: NO_COLOR picks the monochrome theme unless one was chosen
+        let theme_chosen = matches.value_source("theme") == Some(ValueSource::CommandLine)
+            || config.settings.theme.is_some();
+        if !theme_chosen && std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            config.theme = String::from("mono");
+        }
+        Ok(config)
     }
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Self { refresh_rate: 1000 }
+        Self {
+            command: None,
+            config: None,
+            refresh_rate: 1000,
+            source: SourceKind::Sysinfo,
+            steal_threshold: 10.0,
+            history: 300,
+            record: None,
+            batch: false,
+            format: BatchFormat::Csv,
+            granularity: Granularity::Cpu,
+            iterations: None,
+            influx: None,
+            statsd: None,
+            db: None,
+            db_retention: Duration::from_secs(7 * 86400),
+            no_tui: false,
+            mouse: true,
+            view: ViewMode::Core,
+            display: DisplayMode::Full,
+            show_smt: true,
+            socket: None,
+            cpus: None,
+            theme: String::from("default"),
+            gradient: None,
+            usage_thresholds: None,
+            collectors: Collectors::default(),
+            columns: Columns::default(),
+            settings_path: None,
+            settings: Settings::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations_with_units() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(7 * 86400)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+    }
+
+    #[test]
+    fn rejects_malformed_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("10w").is_err());
+        assert!(parse_duration("1.5h").is_err());
+        assert!(parse_duration("-5m").is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_durations() {
+        let err = parse_duration("99999999999999999d").unwrap_err();
+        assert!(err.contains("too long"), "{}", err);
+    }
+
+    #[test]
+    fn parses_cpu_filters() {
+        assert_eq!(parse_cpus("0-3,8"), Ok(vec![0, 1, 2, 3, 8]));
+        assert_eq!(parse_cpus("8191"), Ok(vec![8191]));
+    }
+
+    #[test]
+    fn rejects_empty_or_out_of_range_cpu_filters() {
+        assert!(parse_cpus("").is_err());
+        assert!(parse_cpus("x").is_err());
+        let err = parse_cpus("0-4000000000").unwrap_err();
+        assert!(err.contains("out of range"), "{}", err);
+        assert!(parse_cpus("8192").is_err());
+    }
+
+    #[test]
+    fn rejects_zero_refresh_rate_and_iterations() {
+        assert_eq!(parse_refresh_rate("250"), Ok(250));
+        assert!(parse_refresh_rate("0").is_err());
+        assert_eq!(parse_iterations("3"), Ok(3));
+        assert!(parse_iterations("0").is_err());
     }
 }
@@ -0,0 +1,4 @@
+pub mod query;
+pub mod store;
+
+pub use store::HistoryDb;
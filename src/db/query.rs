@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use super::store::{cutoff_ms, HistoryDb};
+use crate::config::QueryGroup;
+
+/// Aggregate applied to the usage samples of each group
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregate {
+    Avg,
+    Min,
+    Max,
+    /// Nearest-rank percentile, 0-100
+    Percentile(f64),
+}
+
+impl FromStr for Aggregate {
+    type Err = String;
+
+    /// "avg", "min", "max" or "pNN" (e.g. "p95", "p99.9")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "avg" | "mean" => Ok(Aggregate::Avg),
+            "min" => Ok(Aggregate::Min),
+            "max" => Ok(Aggregate::Max),
+            _ => s
+                .strip_prefix('p')
+                .and_then(|p| p.parse::<f64>().ok())
+                .filter(|p| (0.0..=100.0).contains(p))
+                .map(Aggregate::Percentile)
+                .ok_or_else(|| format!("invalid aggregate '{}' (avg, min, max or pNN)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Aggregate::Avg => write!(f, "avg"),
+            Aggregate::Min => write!(f, "min"),
+            Aggregate::Max => write!(f, "max"),
+            Aggregate::Percentile(p) => write!(f, "p{}", p),
+        }
+    }
+}
+
+impl Aggregate {
+    fn apply(&self, values: &mut [f64]) -> f64 {
+        if values.is_empty() {
+            return 0.0;
+        }
+        match self {
+            Aggregate::Avg => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregate::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregate::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregate::Percentile(p) => {
+                values.sort_by(|a, b| a.total_cmp(b));
+                let rank = ((p / 100.0) * values.len() as f64).ceil() as usize;
+                values[rank.clamp(1, values.len()) - 1]
+            }
+        }
+    }
+}
+
+/// Print an aggregate of historical usage per group
+pub fn run(path: &Path, since: Duration, group: QueryGroup, agg: Aggregate) -> Result<()> {
+    let conn = HistoryDb::open_read_only(path)?;
+    let cutoff = cutoff_ms(since);
+
+    // id -> (package, usage samples)
+    let mut groups: BTreeMap<i64, (i64, Vec<f64>)> = BTreeMap::new();
+    let mut collect = |row: &rusqlite::Row| -> rusqlite::Result<()> {
+        let entry = groups.entry(row.get(0)?).or_insert_with(|| (0, Vec::new()));
+        entry.0 = row.get(1)?;
+        entry.1.push(row.get(2)?);
+        Ok(())
+    };
+
+    let (label, kind) = match group {
+        QueryGroup::Cpu => ("CPU", None),
+        QueryGroup::Ccd => ("CCD", Some("ccd")),
+        QueryGroup::Numa => ("NUMA", Some("numa")),
+        QueryGroup::Socket => ("Socket", Some("socket")),
+    };
+    match kind {
+        None => {
+            let mut stmt =
+                conn.prepare("SELECT cpu, package, usage FROM cpu_samples WHERE ts_ms >= ?1")?;
+            let mut rows = stmt.query([cutoff])?;
+            while let Some(row) = rows.next()? {
+                collect(row)?;
+            }
+        }
+        Some(kind) => {
+            let mut stmt = conn.prepare(
+                "SELECT group_id, package, usage FROM group_samples
+                 WHERE kind = ?1 AND ts_ms >= ?2",
+            )?;
+            let mut rows = stmt.query(rusqlite::params![kind, cutoff])?;
+            while let Some(row) = rows.next()? {
+                collect(row)?;
+            }
+        }
+    }
+
+    if groups.is_empty() {
+        println!("No samples in the last {}", format_duration(since));
+        return Ok(());
+    }
+
+    println!(
+        "{} usage per {} over the last {}",
+        agg,
+        label,
+        format_duration(since)
+    );
+    println!(
+        "{:>6} {:>7} {:>8} {:>8}",
+        label,
+        "Package",
+        "Samples",
+        agg.to_string()
+    );
+    for (id, (package, mut values)) in groups {
+        let value = agg.apply(&mut values);
+        println!(
+            "{:>6} {:>7} {:>8} {:>7.1}%",
+            id,
+            package,
+            values.len(),
+            value
+        );
+    }
+
+    Ok(())
+}
+
+/// "2h", "90m", "45s", "7d"
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        s if s % 86400 == 0 && s > 0 => format!("{}d", s / 86400),
+        s if s % 3600 == 0 && s > 0 => format!("{}h", s / 3600),
+        s if s % 60 == 0 && s > 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
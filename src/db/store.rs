@@ -0,0 +1,187 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use crate::export::aggregate::{average_frequency, package_of, smt_thread, socket_cpus};
+use crate::export::Sink;
+use crate::stats::{GroupUsage, SystemStats};
+use crate::topology::ZenTopology;
+
+/// Samples between retention sweeps
+const PRUNE_EVERY: u64 = 60;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cpu_samples (
+    ts_ms       INTEGER NOT NULL,
+    cpu         INTEGER NOT NULL,
+    core        INTEGER NOT NULL,
+    ccd         INTEGER NOT NULL,
+    numa        INTEGER NOT NULL,
+    package     INTEGER NOT NULL,
+    smt_thread  INTEGER NOT NULL,
+    usage       REAL NOT NULL,
+    steal       REAL NOT NULL,
+    guest       REAL NOT NULL,
+    freq_mhz    INTEGER
+);
+CREATE INDEX IF NOT EXISTS cpu_samples_ts ON cpu_samples (ts_ms);
+
+CREATE TABLE IF NOT EXISTS group_samples (
+    ts_ms       INTEGER NOT NULL,
+    kind        TEXT NOT NULL,
+    group_id    INTEGER NOT NULL,
+    package     INTEGER NOT NULL,
+    usage       REAL NOT NULL,
+    min_usage   REAL NOT NULL,
+    max_usage   REAL NOT NULL,
+    freq_mhz    REAL
+);
+CREATE INDEX IF NOT EXISTS group_samples_kind_ts ON group_samples (kind, ts_ms);
+";
+
+/// SQLite store of per-CPU and per-group (CCD, NUMA node, socket) samples
+///
+/// Rows are keyed by topology IDs so they can be aggregated later with
+/// `zentop query`. Rows older than the retention window are deleted as
+/// new samples arrive.
+pub struct HistoryDb {
+    conn: Connection,
+    retention: Duration,
+    inserted: u64,
+}
+
+impl HistoryDb {
+    /// Open (or create) a database at `path`
+    pub fn open(path: &Path, retention: Duration) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open database {}", path.display()))?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create history tables")?;
+
+        let mut db = Self {
+            conn,
+            retention,
+            inserted: 0,
+        };
+        db.prune()?;
+        Ok(db)
+    }
+
+    /// Open an existing database for queries
+    pub fn open_read_only(path: &Path) -> Result<Connection> {
+        Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("Failed to open database {}", path.display()))
+    }
+
+    /// Insert one snapshot in a single transaction
+    pub fn insert(&mut self, topology: &ZenTopology, stats: &SystemStats) -> Result<()> {
+        let ts_ms = unix_ms();
+        let tx = self.conn.transaction()?;
+        {
+            let mut cpu_stmt = tx.prepare_cached(
+                "INSERT INTO cpu_samples
+                 (ts_ms, cpu, core, ccd, numa, package, smt_thread, usage, steal, guest, freq_mhz)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            for usage in &stats.core_usages {
+                let Some(core) = topology.core(usage.core_id) else {
+                    continue;
+                };
+                cpu_stmt.execute(params![
+                    ts_ms,
+                    core.id,
+                    core.physical_id,
+                    core.ccd_id,
+                    core.numa_node,
+                    core.package_id,
+                    smt_thread(topology, core.id),
+                    usage.usage_percent,
+                    usage.steal_percent,
+                    usage.guest_percent,
+                    usage.frequency_mhz,
+                ])?;
+            }
+
+            let mut group_stmt = tx.prepare_cached(
+                "INSERT INTO group_samples
+                 (ts_ms, kind, group_id, package, usage, min_usage, max_usage, freq_mhz)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            let groups = topology
+                .ccds
+                .iter()
+                .map(|c| ("ccd", c.id, c.cores.clone()))
+                .chain(
+                    topology
+                        .numa_nodes
+                        .iter()
+                        .map(|n| ("numa", n.id, n.cores.clone())),
+                )
+                .chain(
+                    (0..topology.packages.max(1)).map(|p| ("socket", p, socket_cpus(topology, p))),
+                );
+            for (kind, id, cpus) in groups {
+                let usages: Vec<_> = cpus
+                    .iter()
+                    .filter_map(|&cpu_id| stats.usage(cpu_id).cloned())
+                    .collect();
+                if usages.is_empty() {
+                    continue;
+                }
+                let group = GroupUsage::from_cores(String::new(), id, &usages);
+                group_stmt.execute(params![
+                    ts_ms,
+                    kind,
+                    id,
+                    package_of(topology, &cpus),
+                    group.usage_percent,
+                    group.min_usage,
+                    group.max_usage,
+                    average_frequency(stats, &cpus),
+                ])?;
+            }
+        }
+        tx.commit().context("Failed to write samples")?;
+
+        self.inserted += 1;
+        if self.inserted % PRUNE_EVERY == 0 {
+            self.prune()?;
+        }
+        Ok(())
+    }
+
+    /// Delete rows older than the retention window
+    fn prune(&mut self) -> Result<()> {
+        let cutoff = cutoff_ms(self.retention);
+        self.conn
+            .execute("DELETE FROM cpu_samples WHERE ts_ms < ?1", [cutoff])?;
+        self.conn
+            .execute("DELETE FROM group_samples WHERE ts_ms < ?1", [cutoff])?;
+        Ok(())
+    }
+}
+
+impl Sink for HistoryDb {
+    fn name(&self) -> &str {
+        "history database"
+    }
+
+    fn send(&mut self, topology: &ZenTopology, stats: &SystemStats) -> Result<()> {
+        self.insert(topology, stats)
+    }
+}
+
+/// Milliseconds since the Unix epoch
+pub fn unix_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Unix time in milliseconds `window` ago, saturating for absurdly long windows
+pub fn cutoff_ms(window: Duration) -> i64 {
+    unix_ms().saturating_sub(i64::try_from(window.as_millis()).unwrap_or(i64::MAX))
+}
@@ -1,41 +1,189 @@
-use std::time::Duration;
+use std::io;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+    self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind,
+};
 
 /// Application events
 #[derive(Debug, Clone, Copy)]
 pub enum Event {
     /// Keyboard event
     Key(KeyEvent),
-    /// Terminal tick (for refresh)
-    Tick,
+    /// Mouse click or wheel event
+    Mouse(MouseEvent),
+    /// New samples arrived, or polled sources are due
+    Sample,
+    /// Time to redraw: state changed and no input is pending
+    Redraw,
     /// Resize event
     Resize(u16, u16),
 }
 
-/// Event handler for keyboard input
+/// Delay before the first poll, long enough for live samplers to prime
+const FIRST_SAMPLE_DELAY: Duration = Duration::from_millis(250);
+
+/// What the event loop's channel carries
+enum Input {
+    Terminal(io::Result<CrosstermEvent>),
+    Wake,
+}
+
+/// Handle that wakes the event loop with an `Event::Sample`
+///
+/// Given to sources that deliver snapshots from their own threads, so each
+/// snapshot is drawn as soon as it arrives.
+#[derive(Clone)]
+pub struct Waker(Sender<Input>);
+
+impl Waker {
+    pub fn wake(&self) {
+        // Nothing to wake once the event loop is gone
+        let _ = self.0.send(Input::Wake);
+    }
+}
+
+/// Event scheduler for keyboard input, sampling and redraws
+///
+/// Terminal input is read on its own thread and shares a channel with
+/// sample wake-ups. Sources that cannot wake the loop (replays) are polled
+/// on a fixed schedule that input cannot delay or advance. Redraws are
+/// coalesced, so a burst of key repeats is handled in full before the next
+/// frame is drawn.
 pub struct EventHandler {
-    tick_rate: Duration,
+    sender: Sender<Input>,
+    receiver: Receiver<Input>,
+    reading: bool,
+    /// Polling period, for sources that cannot wake the loop
+    sample_interval: Option<Duration>,
+    next_sample: Instant,
+    /// Something changed since the last `Redraw`
+    dirty: bool,
+}
+
+impl Default for EventHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl EventHandler {
-    pub fn new(tick_rate_ms: u64) -> Self {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
         Self {
-            tick_rate: Duration::from_millis(tick_rate_ms),
+            sender,
+            receiver,
+            reading: false,
+            sample_interval: None,
+            next_sample: Instant::now(),
+            // Draw the first frame right away
+            dirty: true,
+        }
+    }
+
+    /// Handle for sources to signal new samples
+    pub fn waker(&self) -> Waker {
+        Waker(self.sender.clone())
+    }
+
+    /// Poll for samples every `interval`, or only on wake-ups with `None`
+    pub fn set_sample_interval(&mut self, interval: Option<Duration>) {
+        if interval != self.sample_interval {
+            let delay = match (self.sample_interval, interval) {
+                (_, None) => Duration::ZERO,
+                (None, Some(interval)) => FIRST_SAMPLE_DELAY.min(interval),
+                (Some(_), Some(interval)) => interval,
+            };
+            self.sample_interval = interval;
+            // Later polls follow on the fixed cadence from here
+            self.next_sample = Instant::now() + delay;
         }
     }
 
-    /// Poll for next event
-    pub fn next(&self) -> Result<Event> {
-        if event::poll(self.tick_rate)? {
-            match event::read()? {
-                CrosstermEvent::Key(key) => Ok(Event::Key(key)),
-                CrosstermEvent::Resize(w, h) => Ok(Event::Resize(w, h)),
-                _ => Ok(Event::Tick),
+    /// Read terminal input on a thread, once the terminal is set up
+    fn start_reading(&mut self) {
+        if self.reading {
+            return;
+        }
+        self.reading = true;
+        let sender = self.sender.clone();
+        thread::spawn(move || loop {
+            let input = event::read();
+            let failed = input.is_err();
+            if sender.send(Input::Terminal(input)).is_err() || failed {
+                break;
+            }
+        });
+    }
+
+    /// Wait for the next event
+    pub fn next(&mut self) -> Result<Event> {
+        self.start_reading();
+        loop {
+            let now = Instant::now();
+            if let Some(interval) = self.sample_interval {
+                if now >= self.next_sample {
+                    self.next_sample += interval;
+                    // After a stall, skip missed polls instead of bursting
+                    if self.next_sample <= now {
+                        self.next_sample = now + interval;
+                    }
+                    self.dirty = true;
+                    return Ok(Event::Sample);
+                }
+            }
+
+            // With a redraw pending, only drain input that is already queued
+            let timeout = if self.dirty {
+                Some(Duration::ZERO)
+            } else {
+                self.sample_interval.map(|_| self.next_sample - now)
+            };
+            let received = match timeout {
+                Some(timeout) => self.receiver.recv_timeout(timeout),
+                None => self
+                    .receiver
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            let input = match received {
+                Ok(Input::Wake) => {
+                    self.dirty = true;
+                    return Ok(Event::Sample);
+                }
+                Ok(Input::Terminal(input)) => input?,
+                Err(_) => {
+                    if self.dirty {
+                        self.dirty = false;
+                        return Ok(Event::Redraw);
+                    }
+                    continue;
+                }
+            };
+
+            match input {
+                CrosstermEvent::Key(key) => {
+                    self.dirty = true;
+                    return Ok(Event::Key(key));
+                }
+                CrosstermEvent::Mouse(mouse) => match mouse.kind {
+                    // Motion and release reports change nothing
+                    MouseEventKind::Moved | MouseEventKind::Drag(_) | MouseEventKind::Up(_) => {}
+                    _ => {
+                        self.dirty = true;
+                        return Ok(Event::Mouse(mouse));
+                    }
+                },
+                CrosstermEvent::Resize(w, h) => {
+                    self.dirty = true;
+                    return Ok(Event::Resize(w, h));
+                }
+                // Focus and paste events are not used
+                _ => {}
             }
-        } else {
-            Ok(Event::Tick)
         }
     }
 }
@@ -47,11 +195,31 @@ pub enum KeyAction {
     ViewCore,
     ViewCcd,
     ViewNps,
+    ViewCgroup,
+    ViewIrq,
+    ViewCluster,
     ToggleSmt,
+    ToggleVcpus,
     ToggleHelp,
     CycleDisplayMode,
-    ScrollUp,
-    ScrollDown,
+    CycleTheme,
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    PageUp,
+    PageDown,
+    ScrollTop,
+    ScrollBottom,
+    ToggleChart,
+    ToggleDetail,
+    TogglePause,
+    Step,
+    SeekBackward,
+    SeekForward,
+    Faster,
+    Slower,
+    SaveSettings,
     None,
 }
 
@@ -66,20 +234,47 @@ pub fn handle_key(key: KeyEvent) -> KeyAction {
         KeyCode::Char('c') => KeyAction::ViewCore,
         KeyCode::Char('d') => KeyAction::ViewCcd,
         KeyCode::Char('n') => KeyAction::ViewNps,
+        KeyCode::Char('C') => KeyAction::ViewCgroup,
+        KeyCode::Char('i') => KeyAction::ViewIrq,
+        KeyCode::Char('u') => KeyAction::ViewCluster,
 
         // SMT toggle
         KeyCode::Char('s') => KeyAction::ToggleSmt,
 
+        // Guest vCPU overlay
+        KeyCode::Char('v') => KeyAction::ToggleVcpus,
+
         // Display mode
         KeyCode::Char('m') => KeyAction::CycleDisplayMode,
 
+        // Color theme
+        KeyCode::Char('t') => KeyAction::CycleTheme,
+
         // Help
-        KeyCode::Char('h') => KeyAction::ToggleHelp,
         KeyCode::Char('?') => KeyAction::ToggleHelp,
 
         // Navigation
-        KeyCode::Up | KeyCode::Char('k') => KeyAction::ScrollUp,
-        KeyCode::Down | KeyCode::Char('j') => KeyAction::ScrollDown,
+        KeyCode::Up | KeyCode::Char('k') => KeyAction::CursorUp,
+        KeyCode::Down | KeyCode::Char('j') => KeyAction::CursorDown,
+        KeyCode::Left | KeyCode::Char('h') => KeyAction::CursorLeft,
+        KeyCode::Right | KeyCode::Char('l') => KeyAction::CursorRight,
+        KeyCode::PageUp => KeyAction::PageUp,
+        KeyCode::PageDown => KeyAction::PageDown,
+        KeyCode::Home | KeyCode::Char('g') => KeyAction::ScrollTop,
+        KeyCode::End | KeyCode::Char('G') => KeyAction::ScrollBottom,
+        KeyCode::Enter => KeyAction::ToggleChart,
+        KeyCode::Char('p') => KeyAction::ToggleDetail,
+
+        // Sampling and replay controls
+        KeyCode::Char(' ') => KeyAction::TogglePause,
+        KeyCode::Char('.') => KeyAction::Step,
+        KeyCode::Char('[') => KeyAction::SeekBackward,
+        KeyCode::Char(']') => KeyAction::SeekForward,
+        KeyCode::Char('+') | KeyCode::Char('=') => KeyAction::Faster,
+        KeyCode::Char('-') => KeyAction::Slower,
+
+        // Write current view/display/refresh settings to the config file
+        KeyCode::Char('w') => KeyAction::SaveSettings,
 
         _ => KeyAction::None,
     }
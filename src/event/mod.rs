@@ -1,3 +1,3 @@
 pub mod handler;
 
-pub use handler::{handle_key, Event, EventHandler, KeyAction};
+pub use handler::{handle_key, Event, EventHandler, KeyAction, Waker};
@@ -0,0 +1,143 @@
+use crate::stats::SystemStats;
+use crate::topology::ZenTopology;
+
+/// Average usage of the given CPUs, if any have stats
+pub fn average_usage(stats: &SystemStats, cpus: &[usize]) -> Option<f64> {
+    average(
+        cpus.iter()
+            .filter_map(|&id| stats.usage(id))
+            .map(|u| u.usage_percent as f64),
+    )
+}
+
+/// Average frequency of the given CPUs that report one
+pub fn average_frequency(stats: &SystemStats, cpus: &[usize]) -> Option<f64> {
+    average(
+        cpus.iter()
+            .filter_map(|&id| stats.usage(id)?.frequency_mhz)
+            .map(|f| f as f64),
+    )
+}
+
+/// Package of the first CPU in a group
+pub fn package_of(topology: &ZenTopology, cpus: &[usize]) -> usize {
+    cpus.first()
+        .and_then(|&cpu_id| topology.core(cpu_id))
+        .map_or(0, |c| c.package_id)
+}
+
+/// NUMA node of the first CPU in a group
+pub fn numa_of(topology: &ZenTopology, cpus: &[usize]) -> usize {
+    cpus.first()
+        .and_then(|&cpu_id| topology.core(cpu_id))
+        .map_or(0, |c| c.numa_node)
+}
+
+/// Logical CPUs of a socket
+pub fn socket_cpus(topology: &ZenTopology, package: usize) -> Vec<usize> {
+    topology
+        .cores
+        .iter()
+        .filter(|c| c.package_id == package)
+        .map(|c| c.id)
+        .collect()
+}
+
+/// 1 for the second hardware thread of a core, 0 otherwise
+pub fn smt_thread(topology: &ZenTopology, cpu_id: usize) -> usize {
+    topology
+        .core(cpu_id)
+        .map_or(0, |c| usize::from(c.smt_sibling.is_some_and(|s| s < c.id)))
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::stats::CoreUsage;
+    use crate::topology::CpuCore;
+
+    /// Two sockets of one core with two threads each; CPU 1 is offline, so
+    /// positions and IDs differ from the second CPU on
+    fn topology() -> ZenTopology {
+        let core = |id, physical_id, sibling, package_id| CpuCore {
+            id,
+            physical_id,
+            ccd_id: package_id,
+            numa_node: package_id,
+            package_id,
+            smt_sibling: sibling,
+            ..CpuCore::default()
+        };
+        ZenTopology {
+            cores: vec![
+                core(0, 0, None, 0),
+                core(2, 1, Some(3), 1),
+                core(3, 1, Some(2), 1),
+            ],
+            packages: 2,
+            ..ZenTopology::default()
+        }
+    }
+
+    fn stats() -> SystemStats {
+        let usage = |core_id, usage_percent, frequency_mhz| CoreUsage {
+            core_id,
+            usage_percent,
+            steal_percent: 0.0,
+            guest_percent: 0.0,
+            frequency_mhz,
+        };
+        SystemStats::from_core_usages(
+            vec![
+                usage(0, 10.0, Some(3000)),
+                usage(2, 50.0, Some(3500)),
+                usage(3, 90.0, None),
+            ],
+            Instant::now(),
+        )
+    }
+
+    #[test]
+    fn averages_by_cpu_id() {
+        let stats = stats();
+        assert_eq!(average_usage(&stats, &[2, 3]), Some(70.0));
+        assert_eq!(average_usage(&stats, &[0, 3]), Some(50.0));
+        // CPU 1 is offline and simply ignored
+        assert_eq!(average_usage(&stats, &[1, 2]), Some(50.0));
+        assert_eq!(average_usage(&stats, &[1]), None);
+        assert_eq!(average_usage(&stats, &[]), None);
+    }
+
+    #[test]
+    fn averages_only_cpus_reporting_a_frequency() {
+        let stats = stats();
+        assert_eq!(average_frequency(&stats, &[0, 2, 3]), Some(3250.0));
+        assert_eq!(average_frequency(&stats, &[3]), None);
+    }
+
+    #[test]
+    fn resolves_groups_by_cpu_id() {
+        let topology = topology();
+        assert_eq!(package_of(&topology, &[2, 3]), 1);
+        assert_eq!(numa_of(&topology, &[3]), 1);
+        assert_eq!(package_of(&topology, &[0]), 0);
+        assert_eq!(socket_cpus(&topology, 1), vec![2, 3]);
+        assert!(socket_cpus(&topology, 2).is_empty());
+    }
+
+    #[test]
+    fn numbers_smt_threads_within_a_core() {
+        let topology = topology();
+        assert_eq!(smt_thread(&topology, 0), 0);
+        assert_eq!(smt_thread(&topology, 2), 0);
+        assert_eq!(smt_thread(&topology, 3), 1);
+        assert_eq!(smt_thread(&topology, 1), 0);
+    }
+}
@@ -0,0 +1,137 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+
+use super::aggregate::{
+    average_frequency, average_usage, numa_of, package_of, smt_thread, socket_cpus,
+};
+use super::sink::{host_name, Output, Sink};
+use crate::stats::{GroupUsage, SystemStats};
+use crate::topology::ZenTopology;
+
+/// Writes samples as InfluxDB line protocol
+///
+/// One point per CPU (`zentop_cpu`), CCD (`zentop_ccd`), NUMA node
+/// (`zentop_numa`) and socket (`zentop_socket`), tagged with the host and
+/// the topology IDs it belongs to.
+pub struct InfluxSink {
+    output: Output,
+    host: String,
+}
+
+impl InfluxSink {
+    pub fn new(output: Output) -> Self {
+        Self {
+            output,
+            host: escape_tag(&host_name()),
+        }
+    }
+}
+
+impl Sink for InfluxSink {
+    fn name(&self) -> &str {
+        "InfluxDB"
+    }
+
+    fn send(&mut self, topology: &ZenTopology, stats: &SystemStats) -> Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let host = &self.host;
+        let mut lines = Vec::new();
+
+        for usage in &stats.core_usages {
+            let Some(core) = topology.core(usage.core_id) else {
+                continue;
+            };
+            let mut fields = format!(
+                "usage={},steal={},guest={}",
+                usage.usage_percent, usage.steal_percent, usage.guest_percent
+            );
+            if let Some(freq) = usage.frequency_mhz {
+                fields.push_str(&format!(",freq_mhz={}i", freq));
+            }
+            lines.push(format!(
+                "zentop_cpu,host={},cpu={},ccd={},numa={},package={},smt_thread={} {} {}",
+                host,
+                core.id,
+                core.ccd_id,
+                core.numa_node,
+                core.package_id,
+                smt_thread(topology, core.id),
+                fields,
+                timestamp
+            ));
+        }
+
+        for ccd in &topology.ccds {
+            let usages: Vec<_> = ccd
+                .cores
+                .iter()
+                .filter_map(|&cpu_id| stats.usage(cpu_id).cloned())
+                .collect();
+            if usages.is_empty() {
+                continue;
+            }
+            let group = GroupUsage::from_cores(String::new(), ccd.id, &usages);
+            lines.push(format!(
+                "zentop_ccd,host={},ccd={},numa={},package={} usage={},min_usage={},max_usage={}{} {}",
+                host,
+                ccd.id,
+                numa_of(topology, &ccd.cores),
+                ccd.package_id,
+                group.usage_percent,
+                group.min_usage,
+                group.max_usage,
+                frequency_field(stats, &ccd.cores),
+                timestamp
+            ));
+        }
+
+        for node in &topology.numa_nodes {
+            if let Some(usage) = average_usage(stats, &node.cores) {
+                lines.push(format!(
+                    "zentop_numa,host={},numa={},package={} usage={}{} {}",
+                    host,
+                    node.id,
+                    package_of(topology, &node.cores),
+                    usage,
+                    frequency_field(stats, &node.cores),
+                    timestamp
+                ));
+            }
+        }
+
+        for package in 0..topology.packages.max(1) {
+            let cpus = socket_cpus(topology, package);
+            if let Some(usage) = average_usage(stats, &cpus) {
+                lines.push(format!(
+                    "zentop_socket,host={},package={} usage={}{} {}",
+                    host,
+                    package,
+                    usage,
+                    frequency_field(stats, &cpus),
+                    timestamp
+                ));
+            }
+        }
+
+        self.output.write_lines(&lines)
+    }
+}
+
+/// ",freq_mhz=N" when any CPU in the group reports a frequency
+fn frequency_field(stats: &SystemStats, cpus: &[usize]) -> String {
+    average_frequency(stats, cpus)
+        .map(|f| format!(",freq_mhz={}", f))
+        .unwrap_or_default()
+}
+
+/// Escape commas, spaces and equals signs in a tag value
+fn escape_tag(value: &str) -> String {
+    value
+        .replace(',', "\\,")
+        .replace(' ', "\\ ")
+        .replace('=', "\\=")
+}
@@ -0,0 +1,54 @@
+pub mod aggregate;
+pub mod influx;
+pub mod prometheus;
+pub mod server;
+pub mod sink;
+pub mod statsd;
+
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::config::{Config, SourceKind};
+use crate::db::HistoryDb;
+use crate::stats::sampler;
+use crate::topology::ZenTopology;
+
+pub use influx::InfluxSink;
+pub use sink::{Output, Sink, SinkWorker, Sinks};
+pub use statsd::StatsdSink;
+
+/// Build the output sinks requested on the command line
+pub fn sinks_from_config(config: &Config) -> Result<Vec<Box<dyn Sink>>> {
+    let mut sinks: Vec<Box<dyn Sink>> = Vec::new();
+    if let Some(dest) = &config.influx {
+        sinks.push(Box::new(InfluxSink::new(Output::open(dest)?)));
+    }
+    if let Some(addr) = &config.statsd {
+        sinks.push(Box::new(StatsdSink::new(Output::udp(addr)?)));
+    }
+    if let Some(path) = &config.db {
+        sinks.push(Box::new(HistoryDb::open(path, config.db_retention)?));
+    }
+    Ok(sinks)
+}
+
+/// Feed sinks without the TUI until killed; sink errors go to stderr
+pub fn run_sinks(
+    topology: &ZenTopology,
+    sinks: Vec<Box<dyn Sink>>,
+    source: SourceKind,
+    refresh_rate_ms: u64,
+) -> Result<()> {
+    let mut sinks = Sinks::new(sinks);
+    let mut sampler = sampler(source, topology);
+    let interval = Duration::from_millis(refresh_rate_ms);
+    loop {
+        let stats = sampler.sample();
+        for error in sinks.send(topology, &stats) {
+            eprintln!("zentop: {}", error);
+        }
+        thread::sleep(interval);
+    }
+}
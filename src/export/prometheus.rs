@@ -0,0 +1,198 @@
+use std::fmt::Write;
+
+use super::aggregate::{
+    average_frequency, average_usage, numa_of, package_of, smt_thread, socket_cpus,
+};
+use crate::stats::{CoreUsage, SystemStats, Temperatures};
+use crate::topology::{Ccd, NumaNode, ZenTopology};
+
+/// Render a snapshot in the Prometheus text exposition format
+///
+/// Every CPU, CCD, NUMA node and socket gets usage, frequency and
+/// temperature gauges labeled from the topology. Zen has no per-core
+/// temperature sensor, so CPU and NUMA temperatures come from their CCDs.
+pub fn render_metrics(topology: &ZenTopology, stats: &SystemStats, temps: &Temperatures) -> String {
+    let mut out = String::new();
+
+    let cpu_labels = |cpu_id: usize| {
+        topology.core(cpu_id).map(|c| {
+            format!(
+                "cpu=\"{}\",core=\"{}\",ccd=\"{}\",numa=\"{}\",package=\"{}\",smt_thread=\"{}\"",
+                c.id,
+                c.physical_id,
+                c.ccd_id,
+                c.numa_node,
+                c.package_id,
+                smt_thread(topology, c.id)
+            )
+        })
+    };
+    let cpu_samples = |value: &dyn Fn(&CoreUsage) -> Option<f64>| -> Vec<(String, f64)> {
+        stats
+            .core_usages
+            .iter()
+            .filter_map(|u| Some((cpu_labels(u.core_id)?, value(u)?)))
+            .collect()
+    };
+
+    gauge(
+        &mut out,
+        "zentop_cpu_usage_percent",
+        "CPU usage of a logical CPU",
+        cpu_samples(&|u| Some(u.usage_percent as f64)),
+    );
+    gauge(
+        &mut out,
+        "zentop_cpu_steal_percent",
+        "Time stolen by the hypervisor from a logical CPU",
+        cpu_samples(&|u| Some(u.steal_percent as f64)),
+    );
+    gauge(
+        &mut out,
+        "zentop_cpu_frequency_mhz",
+        "Current frequency of a logical CPU",
+        cpu_samples(&|u| u.frequency_mhz.map(|f| f as f64)),
+    );
+    gauge(
+        &mut out,
+        "zentop_cpu_temperature_celsius",
+        "Temperature of the CCD a logical CPU belongs to",
+        cpu_samples(&|u| temps.cpu(topology, u.core_id).map(f64::from)),
+    );
+
+    // CCDs
+    let ccd_labels = |ccd: &Ccd| {
+        let numa = numa_of(topology, &ccd.cores);
+        format!(
+            "ccd=\"{}\",numa=\"{}\",package=\"{}\"",
+            ccd.id, numa, ccd.package_id
+        )
+    };
+    gauge(
+        &mut out,
+        "zentop_ccd_usage_percent",
+        "Average CPU usage of a CCD",
+        topology
+            .ccds
+            .iter()
+            .filter_map(|c| Some((ccd_labels(c), average_usage(stats, &c.cores)?)))
+            .collect(),
+    );
+    gauge(
+        &mut out,
+        "zentop_ccd_frequency_mhz",
+        "Average frequency of a CCD",
+        topology
+            .ccds
+            .iter()
+            .filter_map(|c| Some((ccd_labels(c), average_frequency(stats, &c.cores)?)))
+            .collect(),
+    );
+    gauge(
+        &mut out,
+        "zentop_ccd_temperature_celsius",
+        "CCD temperature (k10temp Tccd)",
+        topology
+            .ccds
+            .iter()
+            .filter_map(|c| Some((ccd_labels(c), *temps.ccds.get(&c.id)? as f64)))
+            .collect(),
+    );
+
+    // NUMA nodes
+    let numa_labels = |node: &NumaNode| {
+        let package = package_of(topology, &node.cores);
+        format!("numa=\"{}\",package=\"{}\"", node.id, package)
+    };
+    gauge(
+        &mut out,
+        "zentop_numa_usage_percent",
+        "Average CPU usage of a NUMA node",
+        topology
+            .numa_nodes
+            .iter()
+            .filter_map(|n| Some((numa_labels(n), average_usage(stats, &n.cores)?)))
+            .collect(),
+    );
+    gauge(
+        &mut out,
+        "zentop_numa_frequency_mhz",
+        "Average frequency of a NUMA node",
+        topology
+            .numa_nodes
+            .iter()
+            .filter_map(|n| Some((numa_labels(n), average_frequency(stats, &n.cores)?)))
+            .collect(),
+    );
+    gauge(
+        &mut out,
+        "zentop_numa_temperature_celsius",
+        "Hottest CCD temperature in a NUMA node",
+        topology
+            .numa_nodes
+            .iter()
+            .filter_map(|n| {
+                let hottest = topology
+                    .ccds_for_cpus(&n.cores)
+                    .iter()
+                    .filter_map(|id| temps.ccds.get(id).copied())
+                    .reduce(f32::max)?;
+                Some((numa_labels(n), hottest as f64))
+            })
+            .collect(),
+    );
+
+    // Sockets
+    let packages = 0..topology.packages.max(1);
+    gauge(
+        &mut out,
+        "zentop_socket_usage_percent",
+        "Average CPU usage of a socket",
+        packages
+            .clone()
+            .filter_map(|p| {
+                let usage = average_usage(stats, &socket_cpus(topology, p))?;
+                Some((format!("package=\"{}\"", p), usage))
+            })
+            .collect(),
+    );
+    gauge(
+        &mut out,
+        "zentop_socket_frequency_mhz",
+        "Average frequency of a socket",
+        packages
+            .clone()
+            .filter_map(|p| {
+                let frequency = average_frequency(stats, &socket_cpus(topology, p))?;
+                Some((format!("package=\"{}\"", p), frequency))
+            })
+            .collect(),
+    );
+    gauge(
+        &mut out,
+        "zentop_socket_temperature_celsius",
+        "Socket control temperature (k10temp Tctl)",
+        packages
+            .filter_map(|p| {
+                Some((
+                    format!("package=\"{}\"", p),
+                    *temps.packages.get(&p)? as f64,
+                ))
+            })
+            .collect(),
+    );
+
+    out
+}
+
+/// Write one gauge family; families without samples are omitted
+fn gauge(out: &mut String, name: &str, help: &str, samples: Vec<(String, f64)>) {
+    if samples.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+}
@@ -0,0 +1,149 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use super::prometheus::render_metrics;
+use crate::config::SourceKind;
+use crate::stats::{read_temperatures, sampler};
+use crate::topology::ZenTopology;
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Most bytes read of a request line and its headers
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+/// Most header lines accepted per request
+const MAX_HEADERS: usize = 64;
+
+/// Connections served at once; further ones are closed right away
+const MAX_CONNECTIONS: usize = 16;
+
+/// How long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serve `/metrics` over HTTP until killed
+///
+/// A background thread samples every `refresh_rate_ms` and replaces the
+/// rendered page, so scrapes never wait on the collector.
+pub fn run(
+    topology: ZenTopology,
+    listen: SocketAddr,
+    source: SourceKind,
+    refresh_rate_ms: u64,
+) -> Result<()> {
+    let listener =
+        TcpListener::bind(listen).with_context(|| format!("Failed to listen on {}", listen))?;
+    eprintln!("zentop: serving metrics on http://{}/metrics", listen);
+
+    let metrics = Arc::new(Mutex::new(None));
+    let interval = Duration::from_millis(refresh_rate_ms);
+
+    {
+        let metrics = Arc::clone(&metrics);
+        thread::spawn(move || {
+            let mut sampler = sampler(source, &topology);
+            loop {
+                let stats = sampler.sample();
+                let temps = read_temperatures(&topology);
+                let page = render_metrics(&topology, &stats, &temps);
+                *metrics.lock().unwrap() = Some(page);
+                thread::sleep(interval);
+            }
+        });
+    }
+
+    let open = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        if open.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+            open.fetch_sub(1, Ordering::Relaxed);
+            continue;
+        }
+        let metrics = Arc::clone(&metrics);
+        let open = Arc::clone(&open);
+        thread::spawn(move || {
+            // Scrapers that hang up early are not our problem
+            let _ = handle_connection(stream, &metrics);
+            open.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    Ok(())
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    metrics: &Mutex<Option<String>>,
+) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    // "GET /metrics HTTP/1.1"; headers are read and ignored
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    let mut headers = 0;
+    let complete = loop {
+        header.clear();
+        if reader.read_line(&mut header)? == 0 {
+            // Size limit reached or client hung up before the blank line
+            break false;
+        }
+        if header.trim_end().is_empty() {
+            break true;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            break false;
+        }
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next(), parts.next());
+
+    let (status, content_type, body) = match (method, path) {
+        _ if !complete => (
+            "431 Request Header Fields Too Large",
+            "text/plain; charset=utf-8",
+            String::from("request too large\n"),
+        ),
+        (Some("GET"), Some("/metrics")) => {
+            match metrics.lock().unwrap().clone() {
+                Some(page) => ("200 OK", CONTENT_TYPE, page),
+                None => (
+                    "503 Service Unavailable",
+                    "text/plain; charset=utf-8",
+                    String::from("no sample yet\n"),
+                ),
+            }
+        }
+        (Some("GET"), Some("/")) => (
+            "200 OK",
+            "text/plain; charset=utf-8",
+            String::from("zentop exporter - metrics at /metrics\n"),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            String::from("not found\n"),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
@@ -0,0 +1,217 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use anyhow::{Context, Result};
+
+use crate::stats::SystemStats;
+use crate::topology::ZenTopology;
+
+/// Keep datagrams under a typical MTU so they are not fragmented
+const MAX_DATAGRAM: usize = 1400;
+
+/// Most snapshots a failing sink skips before it is tried again
+const MAX_BACKOFF: u32 = 64;
+
+/// Push-based output fed with every snapshot
+pub trait Sink: Send {
+    /// Short description for error messages
+    fn name(&self) -> &str;
+
+    fn send(&mut self, topology: &ZenTopology, stats: &SystemStats) -> Result<()>;
+}
+
+/// A sink and its error backoff
+struct Feed {
+    sink: Box<dyn Sink>,
+    /// Consecutive failed sends
+    failures: u32,
+    /// Snapshots left to skip before the next attempt
+    skip: u32,
+}
+
+/// Sinks fed together, where one failing sink does not stop the others
+///
+/// After an error a sink skips 1, 2, 4, ... up to `MAX_BACKOFF` snapshots
+/// before it is tried again, so a dead collector or full disk is not
+/// retried on every refresh.
+#[derive(Default)]
+pub struct Sinks {
+    feeds: Vec<Feed>,
+}
+
+impl Sinks {
+    pub fn new(sinks: Vec<Box<dyn Sink>>) -> Self {
+        let mut feeds = Self::default();
+        feeds.extend(sinks);
+        feeds
+    }
+
+    pub fn extend(&mut self, sinks: Vec<Box<dyn Sink>>) {
+        self.feeds.extend(sinks.into_iter().map(|sink| Feed {
+            sink,
+            failures: 0,
+            skip: 0,
+        }));
+    }
+
+    /// Send a snapshot to every sink that is not backing off; returns a
+    /// message for each send that failed
+    pub fn send(&mut self, topology: &ZenTopology, stats: &SystemStats) -> Vec<String> {
+        let mut errors = Vec::new();
+        for feed in &mut self.feeds {
+            if feed.skip > 0 {
+                feed.skip -= 1;
+                continue;
+            }
+            match feed.sink.send(topology, stats) {
+                Ok(()) => feed.failures = 0,
+                Err(e) => {
+                    feed.failures += 1;
+                    feed.skip = 1u32
+                        .checked_shl(feed.failures - 1)
+                        .unwrap_or(MAX_BACKOFF)
+                        .min(MAX_BACKOFF);
+                    errors.push(format!(
+                        "{} export failed: {:#} (retrying after {} samples)",
+                        feed.sink.name(),
+                        e,
+                        feed.skip
+                    ));
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Work handed to the sink thread
+enum Job {
+    /// The active host changed (cluster sessions)
+    Topology(ZenTopology),
+    Snapshot(SystemStats),
+}
+
+/// Feeds sinks on their own thread
+///
+/// Database transactions, retention sweeps and network sends can take a
+/// while, so the UI thread only queues snapshots and collects errors.
+pub struct SinkWorker {
+    jobs: Sender<Job>,
+    errors: Receiver<String>,
+}
+
+impl SinkWorker {
+    pub fn spawn(topology: ZenTopology, sinks: Vec<Box<dyn Sink>>) -> Self {
+        let (jobs, job_receiver) = mpsc::channel();
+        let (error_sender, errors) = mpsc::channel();
+        thread::spawn(move || {
+            let mut topology = topology;
+            let mut sinks = Sinks::new(sinks);
+            for job in job_receiver {
+                match job {
+                    Job::Topology(new) => topology = new,
+                    Job::Snapshot(stats) => {
+                        for error in sinks.send(&topology, &stats) {
+                            if error_sender.send(error).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        Self { jobs, errors }
+    }
+
+    /// Queue a snapshot for every sink
+    pub fn send(&self, stats: SystemStats) {
+        // The thread only exits once the worker is dropped
+        let _ = self.jobs.send(Job::Snapshot(stats));
+    }
+
+    /// Describe later snapshots with a different topology
+    pub fn set_topology(&self, topology: ZenTopology) {
+        let _ = self.jobs.send(Job::Topology(topology));
+    }
+
+    /// Sink errors reported since the last call
+    pub fn errors(&self) -> Vec<String> {
+        self.errors.try_iter().collect()
+    }
+}
+
+/// Destination for line-oriented sink output
+pub enum Output {
+    File(BufWriter<File>),
+    Udp {
+        socket: UdpSocket,
+        target: SocketAddr,
+    },
+}
+
+impl Output {
+    /// `udp://host:port` or a file path (appended to)
+    pub fn open(dest: &str) -> Result<Self> {
+        match dest.strip_prefix("udp://") {
+            Some(addr) => Self::udp(addr),
+            None => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(dest)
+                    .with_context(|| format!("Failed to open {}", dest))?;
+                Ok(Output::File(BufWriter::new(file)))
+            }
+        }
+    }
+
+    pub fn udp(addr: &str) -> Result<Self> {
+        let target = addr
+            .to_socket_addrs()
+            .with_context(|| format!("Failed to resolve {}", addr))?
+            .next()
+            .with_context(|| format!("No address for {}", addr))?;
+        let bind: SocketAddr = if target.is_ipv4() {
+            "0.0.0.0:0".parse()?
+        } else {
+            "[::]:0".parse()?
+        };
+        let socket = UdpSocket::bind(bind).context("Failed to bind UDP socket")?;
+        Ok(Output::Udp { socket, target })
+    }
+
+    /// Write newline-terminated lines, packing as many as fit per datagram
+    pub fn write_lines(&mut self, lines: &[String]) -> Result<()> {
+        match self {
+            Output::File(writer) => {
+                for line in lines {
+                    writeln!(writer, "{}", line)?;
+                }
+                writer.flush()?;
+            }
+            Output::Udp { socket, target } => {
+                let mut packet = String::new();
+                for line in lines {
+                    if !packet.is_empty() && packet.len() + line.len() + 1 > MAX_DATAGRAM {
+                        socket.send_to(packet.as_bytes(), *target)?;
+                        packet.clear();
+                    }
+                    packet.push_str(line);
+                    packet.push('\n');
+                }
+                if !packet.is_empty() {
+                    socket.send_to(packet.as_bytes(), *target)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Local host name used to tag pushed samples
+pub fn host_name() -> String {
+    sysinfo::System::host_name().unwrap_or_else(|| String::from("localhost"))
+}
@@ -0,0 +1,80 @@
+use anyhow::Result;
+
+use super::aggregate::{average_frequency, average_usage, socket_cpus};
+use super::sink::{host_name, Output, Sink};
+use crate::stats::SystemStats;
+use crate::topology::ZenTopology;
+
+/// Sends samples as StatsD gauges
+///
+/// StatsD has no tags, so the topology is encoded in the metric path:
+/// `zentop.<host>.{cpu,ccd,numa,socket}.<id>.{usage,steal,freq_mhz}`.
+pub struct StatsdSink {
+    output: Output,
+    prefix: String,
+}
+
+impl StatsdSink {
+    pub fn new(output: Output) -> Self {
+        // Dots would add path levels
+        let host = host_name().replace('.', "_");
+        Self {
+            output,
+            prefix: format!("zentop.{}", host),
+        }
+    }
+
+    fn gauge(&self, lines: &mut Vec<String>, path: &str, value: f64) {
+        lines.push(format!("{}.{}:{}|g", self.prefix, path, value));
+    }
+}
+
+impl Sink for StatsdSink {
+    fn name(&self) -> &str {
+        "StatsD"
+    }
+
+    fn send(&mut self, topology: &ZenTopology, stats: &SystemStats) -> Result<()> {
+        let mut lines = Vec::new();
+
+        for usage in &stats.core_usages {
+            let id = usage.core_id;
+            self.gauge(
+                &mut lines,
+                &format!("cpu.{}.usage", id),
+                usage.usage_percent as f64,
+            );
+            self.gauge(
+                &mut lines,
+                &format!("cpu.{}.steal", id),
+                usage.steal_percent as f64,
+            );
+            if let Some(freq) = usage.frequency_mhz {
+                self.gauge(&mut lines, &format!("cpu.{}.freq_mhz", id), freq as f64);
+            }
+        }
+
+        let groups = topology
+            .ccds
+            .iter()
+            .map(|c| ("ccd", c.id, c.cores.clone()))
+            .chain(
+                topology
+                    .numa_nodes
+                    .iter()
+                    .map(|n| ("numa", n.id, n.cores.clone())),
+            )
+            .chain((0..topology.packages.max(1)).map(|p| ("socket", p, socket_cpus(topology, p))));
+
+        for (kind, id, cpus) in groups {
+            if let Some(usage) = average_usage(stats, &cpus) {
+                self.gauge(&mut lines, &format!("{}.{}.usage", kind, id), usage);
+            }
+            if let Some(freq) = average_frequency(stats, &cpus) {
+                self.gauge(&mut lines, &format!("{}.{}.freq_mhz", kind, id), freq);
+            }
+        }
+
+        self.output.write_lines(&lines)
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::Instant;
+
+use super::pci::{irq_device_map, scan_pci_devices, PciDevice};
+use super::types::{InterruptStats, IrqLine};
+use crate::topology::parse_cpu_list;
+
+/// A row of /proc/interrupts or /proc/softirqs
+struct CounterRow {
+    name: String,
+    /// Cumulative counts indexed by CPU ID
+    counts: Vec<u64>,
+    description: String,
+}
+
+/// Interrupt rate collector for /proc/interrupts and /proc/softirqs
+pub struct InterruptCollector {
+    devices: HashMap<u32, PciDevice>,
+    prev_irqs: HashMap<String, Vec<u64>>,
+    prev_softirqs: Vec<u64>,
+    last_refresh: Option<Instant>,
+}
+
+impl InterruptCollector {
+    pub fn new() -> Self {
+        Self {
+            devices: irq_device_map(&scan_pci_devices()),
+            prev_irqs: HashMap::new(),
+            prev_softirqs: Vec::new(),
+            last_refresh: None,
+        }
+    }
+
+    /// Read counters and return rates since the previous refresh
+    ///
+    /// The first call after construction only primes the counters and
+    /// reports zero rates.
+    pub fn refresh(&mut self) -> InterruptStats {
+        let now = Instant::now();
+        let elapsed = self
+            .last_refresh
+            .map(|t| now.duration_since(t).as_secs_f32())
+            .unwrap_or(0.0);
+        self.last_refresh = Some(now);
+
+        let rate = |now: u64, prev: Option<&u64>| match prev {
+            Some(&prev) if elapsed > 0.0 => now.saturating_sub(prev) as f32 / elapsed,
+            _ => 0.0,
+        };
+
+        // Hard interrupts
+        let rows = read_counter_table("/proc/interrupts");
+        let cpu_count = rows.iter().map(|r| r.counts.len()).max().unwrap_or(0);
+        let mut cpu_hardirq_rate = vec![0.0; cpu_count];
+        let mut lines = Vec::with_capacity(rows.len());
+        let mut prev_irqs = HashMap::with_capacity(rows.len());
+
+        for row in rows {
+            let prev = self.prev_irqs.get(&row.name);
+            let per_cpu_rate: Vec<f32> = row
+                .counts
+                .iter()
+                .enumerate()
+                .map(|(cpu_id, &count)| rate(count, prev.and_then(|p| p.get(cpu_id))))
+                .collect();
+
+            for (total, r) in cpu_hardirq_rate.iter_mut().zip(&per_cpu_rate) {
+                *total += r;
+            }
+
+            let number = row.name.parse::<u32>().ok();
+            lines.push(IrqLine {
+                per_cpu_rate,
+                affinity: number.map(read_affinity).unwrap_or_default(),
+                device: number.and_then(|n| self.devices.get(&n).cloned()),
+                irq: row.name.clone(),
+                description: row.description,
+            });
+            prev_irqs.insert(row.name, row.counts);
+        }
+        self.prev_irqs = prev_irqs;
+
+        // Softirqs, summed across all types per CPU
+        let mut softirqs: Vec<u64> = Vec::new();
+        for row in read_counter_table("/proc/softirqs") {
+            if softirqs.len() < row.counts.len() {
+                softirqs.resize(row.counts.len(), 0);
+            }
+            for (total, count) in softirqs.iter_mut().zip(&row.counts) {
+                *total += count;
+            }
+        }
+        let cpu_softirq_rate = softirqs
+            .iter()
+            .enumerate()
+            .map(|(cpu_id, &count)| rate(count, self.prev_softirqs.get(cpu_id)))
+            .collect();
+        self.prev_softirqs = softirqs;
+
+        InterruptStats {
+            lines,
+            cpu_hardirq_rate,
+            cpu_softirq_rate,
+        }
+    }
+}
+
+impl Default for InterruptCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read the current smp_affinity_list of an IRQ
+pub fn read_affinity(irq: u32) -> Vec<usize> {
+    fs::read_to_string(format!("/proc/irq/{}/smp_affinity_list", irq))
+        .map(|s| parse_cpu_list(&s))
+        .unwrap_or_default()
+}
+
+fn read_counter_table(path: &str) -> Vec<CounterRow> {
+    fs::read_to_string(path)
+        .map(|content| parse_counter_table(&content))
+        .unwrap_or_default()
+}
+
+/// Parse a per-CPU counter table whose header lists "CPU0 CPU1 ..."
+///
+/// Offline CPUs are omitted from the header, so columns are mapped back to
+/// CPU IDs through it.
+fn parse_counter_table(content: &str) -> Vec<CounterRow> {
+    let mut lines = content.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+
+    let columns: Vec<usize> = header
+        .split_whitespace()
+        .filter_map(|col| col.strip_prefix("CPU")?.parse().ok())
+        .collect();
+    let cpu_count = columns.iter().max().map_or(0, |&max| max + 1);
+
+    lines
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let mut fields = rest.split_whitespace().peekable();
+
+            let mut counts = vec![0; cpu_count];
+            let mut seen = 0;
+            for &cpu_id in &columns {
+                match fields.peek().and_then(|f| f.parse::<u64>().ok()) {
+                    Some(count) => {
+                        counts[cpu_id] = count;
+                        fields.next();
+                        seen += 1;
+                    }
+                    None => break,
+                }
+            }
+
+            // Rows like "ERR:" and "MIS:" carry a single global count
+            if seen < columns.len() {
+                return None;
+            }
+
+            Some(CounterRow {
+                name: name.trim().to_string(),
+                counts,
+                description: fields.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// /proc/interrupts with CPU 2 offline, so its column is missing
+    const INTERRUPTS: &str = "\
+           CPU0       CPU1       CPU3
+  0:         44          0          0   IO-APIC   2-edge      timer
+ 24:     104512          0       8710   PCI-MSI 65536-edge      nvme0q0
+ 25:          0      51230          0   IR-PCI-MSIX-0000:41:00.0    1-edge      nvme0q1
+NMI:         12         10         11   Non-maskable interrupts
+LOC:    9123456    8765432    8654321   Local timer interrupts
+ERR:          0
+MIS:          0
+";
+
+    const SOFTIRQS: &str = "\
+                    CPU0       CPU1
+          HI:          1          0
+       TIMER:     201234     198765
+      NET_RX:       5123       4987
+";
+
+    #[test]
+    fn maps_columns_to_cpu_ids_through_the_header() {
+        let rows = parse_counter_table(INTERRUPTS);
+        let names: Vec<&str> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["0", "24", "25", "NMI", "LOC"]);
+
+        let nvme = &rows[1];
+        assert_eq!(nvme.counts, vec![104512, 0, 0, 8710]);
+        assert_eq!(nvme.description, "PCI-MSI 65536-edge nvme0q0");
+
+        let local = &rows[4];
+        assert_eq!(local.counts, vec![9123456, 8765432, 0, 8654321]);
+        assert_eq!(local.description, "Local timer interrupts");
+    }
+
+    #[test]
+    fn reads_softirq_tables_without_descriptions() {
+        let rows = parse_counter_table(SOFTIRQS);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1].name, "TIMER");
+        assert_eq!(rows[1].counts, vec![201234, 198765]);
+        assert_eq!(rows[1].description, "");
+    }
+
+    #[test]
+    fn empty_input_yields_no_rows() {
+        assert!(parse_counter_table("").is_empty());
+        assert!(parse_counter_table("           CPU0\n").is_empty());
+    }
+}
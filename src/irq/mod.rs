@@ -0,0 +1,7 @@
+pub mod collector;
+pub mod pci;
+pub mod plan;
+pub mod types;
+
+pub use collector::InterruptCollector;
+pub use types::*;
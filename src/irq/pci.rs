@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// sysfs directory listing PCI devices
+const PCI_DEVICES: &str = "/sys/bus/pci/devices";
+
+/// A PCI device and the IRQs it raises
+#[derive(Debug, Clone)]
+pub struct PciDevice {
+    /// PCI address (e.g. "0000:41:00.0")
+    pub address: String,
+    /// Bound driver name (e.g. "nvme", "mlx5_core")
+    pub driver: Option<String>,
+    /// Local NUMA node, if the platform reports one
+    pub numa_node: Option<usize>,
+    /// MSI/MSI-X and legacy IRQ numbers
+    pub irqs: Vec<u32>,
+}
+
+/// Enumerate PCI devices from sysfs
+pub fn scan_pci_devices() -> Vec<PciDevice> {
+    let Ok(entries) = fs::read_dir(PCI_DEVICES) else {
+        return Vec::new();
+    };
+
+    let mut devices: Vec<PciDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let address = entry.file_name().to_str()?.to_string();
+            Some(read_device(&entry.path(), address))
+        })
+        .collect();
+
+    devices.sort_by(|a, b| a.address.cmp(&b.address));
+    devices
+}
+
+/// Map IRQ numbers to the device raising them
+pub fn irq_device_map(devices: &[PciDevice]) -> HashMap<u32, PciDevice> {
+    devices
+        .iter()
+        .flat_map(|dev| dev.irqs.iter().map(move |&irq| (irq, dev.clone())))
+        .collect()
+}
+
+fn read_device(path: &Path, address: String) -> PciDevice {
+    // The kernel reports -1 when the device has no NUMA affinity
+    let numa_node = fs::read_to_string(path.join("numa_node"))
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .and_then(|n| usize::try_from(n).ok());
+
+    let driver = fs::read_link(path.join("driver"))
+        .ok()
+        .and_then(|link| link.file_name()?.to_str().map(String::from));
+
+    let mut irqs: Vec<u32> = fs::read_dir(path.join("msi_irqs"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Legacy INTx line (0 means none assigned)
+    if let Some(irq) = fs::read_to_string(path.join("irq"))
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .filter(|&irq| irq != 0)
+    {
+        irqs.push(irq);
+    }
+
+    irqs.sort();
+    irqs.dedup();
+
+    PciDevice {
+        address,
+        driver,
+        numa_node,
+        irqs,
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+use anyhow::{bail, Result};
+
+use super::collector::InterruptCollector;
+use super::types::IrqLine;
+use crate::topology::{format_cpu_list, parse_cpu_list, ZenTopology};
+
+/// A proposed affinity for a single device IRQ
+#[derive(Debug, Clone)]
+pub struct AffinityChange {
+    pub irq: u32,
+    pub action: String,
+    pub device: String,
+    pub driver: String,
+    pub numa_node: usize,
+    pub current: Vec<usize>,
+    pub proposed: Vec<usize>,
+}
+
+impl AffinityChange {
+    pub fn is_noop(&self) -> bool {
+        self.current == self.proposed
+    }
+}
+
+/// Propose an IRQ layout that keeps each device's queues on its local NUMA
+/// node, spread round-robin across that node's CCDs, avoiding isolated CPUs
+/// and SMT siblings.
+pub fn plan_affinity(
+    topology: &ZenTopology,
+    lines: &[IrqLine],
+    isolated: &[usize],
+) -> Vec<AffinityChange> {
+    // Per-node CPU rotation and cursor, so devices sharing a node interleave
+    let mut rotations: HashMap<usize, (Vec<usize>, usize)> = HashMap::new();
+    let mut changes = Vec::new();
+
+    for line in lines {
+        let (Ok(irq), Some(device)) = (line.irq.parse::<u32>(), line.device.as_ref()) else {
+            continue;
+        };
+        let Some(numa_node) = device.numa_node else {
+            continue;
+        };
+        if line.affinity.is_empty() {
+            continue;
+        }
+
+        let (cpus, cursor) = rotations
+            .entry(numa_node)
+            .or_insert_with(|| (node_rotation(topology, numa_node, isolated), 0));
+        if cpus.is_empty() {
+            continue;
+        }
+
+        let cpu = cpus[*cursor % cpus.len()];
+        *cursor += 1;
+
+        changes.push(AffinityChange {
+            irq,
+            action: line.action().to_string(),
+            device: device.address.clone(),
+            driver: device.driver.clone().unwrap_or_else(|| String::from("-")),
+            numa_node,
+            current: line.affinity.clone(),
+            proposed: vec![cpu],
+        });
+    }
+
+    changes.sort_by_key(|c| c.irq);
+    changes
+}
+
+/// CPUs of a NUMA node ordered round-robin across its CCDs
+///
+/// Only the first SMT thread of each core is used, so IRQs never share a
+/// physical core.
+fn node_rotation(topology: &ZenTopology, numa_node: usize, isolated: &[usize]) -> Vec<usize> {
+    let mut by_ccd: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for core in &topology.cores {
+        if core.numa_node != numa_node || isolated.contains(&core.id) {
+            continue;
+        }
+        if core.smt_sibling.is_some_and(|s| s < core.id) {
+            continue;
+        }
+        by_ccd.entry(core.ccd_id).or_default().push(core.id);
+    }
+
+    let depth = by_ccd.values().map(Vec::len).max().unwrap_or(0);
+    (0..depth)
+        .flat_map(|i| by_ccd.values().filter_map(move |cpus| cpus.get(i).copied()))
+        .collect()
+}
+
+/// CPUs isolated from the scheduler (isolcpus=)
+pub fn read_isolated_cpus() -> Vec<usize> {
+    fs::read_to_string("/sys/devices/system/cpu/isolated")
+        .map(|s| parse_cpu_list(&s))
+        .unwrap_or_default()
+}
+
+/// Print the proposed layout as a diff and optionally write it
+pub fn run(topology: &ZenTopology, apply: bool) -> Result<()> {
+    let lines = InterruptCollector::new().refresh().lines;
+    let isolated = read_isolated_cpus();
+    let changes = plan_affinity(topology, &lines, &isolated);
+
+    if changes.is_empty() {
+        println!("No PCI device IRQs with a known NUMA node found.");
+        return Ok(());
+    }
+
+    if !isolated.is_empty() {
+        println!("Avoiding isolated CPUs: {}", format_cpu_list(&isolated));
+    }
+
+    let pending: Vec<&AffinityChange> = changes.iter().filter(|c| !c.is_noop()).collect();
+
+    // Text columns grow to fit the longest entry; the proposed affinity
+    // lines up under the current one
+    let width = |min: usize, len: fn(&AffinityChange) -> usize| {
+        pending.iter().map(|c| len(c)).fold(min, usize::max)
+    };
+    let device_w = width(14, |c| c.device.len());
+    let driver_w = width(12, |c| c.driver.len());
+    let action_w = width(20, |c| c.action.len());
+    let indent = 6 + 2 + device_w + 1 + driver_w + 1 + 4 + 2 + action_w + 1;
+
+    println!(
+        "{:>6}  {:<device_w$} {:<driver_w$} {:>4}  {:<action_w$} AFFINITY",
+        "IRQ", "DEVICE", "DRIVER", "NUMA", "ACTION"
+    );
+    for change in &pending {
+        println!(
+            "{:>6}  {:<device_w$} {:<driver_w$} {:>4}  {:<action_w$} - {}",
+            change.irq,
+            change.device,
+            change.driver,
+            change.numa_node,
+            change.action,
+            format_cpu_list(&change.current)
+        );
+        println!("{:indent$}+ {}", "", format_cpu_list(&change.proposed));
+    }
+    println!(
+        "{} IRQs to change, {} already in place",
+        pending.len(),
+        changes.len() - pending.len()
+    );
+
+    if !apply {
+        if !pending.is_empty() {
+            println!("Run with --apply to write /proc/irq/*/smp_affinity_list");
+        }
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for change in &pending {
+        let path = format!("/proc/irq/{}/smp_affinity_list", change.irq);
+        if let Err(e) = fs::write(&path, format_cpu_list(&change.proposed)) {
+            // Kernel-managed IRQs (e.g. NVMe queues) reject affinity changes
+            eprintln!("IRQ {}: failed to write {}: {}", change.irq, path, e);
+            failed += 1;
+        }
+    }
+    println!(
+        "Applied {} of {} changes",
+        pending.len() - failed,
+        pending.len()
+    );
+
+    if failed > 0 {
+        bail!("{} IRQ affinity writes failed", failed);
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use super::pci::PciDevice;
+
+/// A single line of /proc/interrupts with per-CPU rates
+#[derive(Debug, Clone)]
+pub struct IrqLine {
+    /// IRQ number or symbolic name (e.g. "123", "LOC", "NMI")
+    pub irq: String,
+    /// Chip/type/action description (e.g. "IR-PCI-MSIX-0000:41:00.0 1-edge nvme0q1")
+    pub description: String,
+    /// Interrupts per second on each logical CPU, indexed by CPU ID
+    pub per_cpu_rate: Vec<f32>,
+    /// Current smp_affinity_list (numeric IRQs only)
+    pub affinity: Vec<usize>,
+    /// PCI device raising this IRQ, if known
+    pub device: Option<PciDevice>,
+}
+
+impl IrqLine {
+    /// Rate on a single CPU
+    pub fn rate_on(&self, cpu_id: usize) -> f32 {
+        self.per_cpu_rate.get(cpu_id).copied().unwrap_or(0.0)
+    }
+
+    /// Action name, usually the last word of the description (e.g. "nvme0q1")
+    pub fn action(&self) -> &str {
+        self.description
+            .split_whitespace()
+            .last()
+            .unwrap_or(&self.irq)
+    }
+}
+
+/// Interrupt and softirq rates over the last refresh interval
+#[derive(Debug, Clone, Default)]
+pub struct InterruptStats {
+    pub lines: Vec<IrqLine>,
+    /// Hard interrupts per second, indexed by CPU ID
+    pub cpu_hardirq_rate: Vec<f32>,
+    /// Softirqs per second, indexed by CPU ID
+    pub cpu_softirq_rate: Vec<f32>,
+}
+
+impl InterruptStats {
+    /// Hard + soft interrupt rate on a CPU
+    pub fn cpu_rate(&self, cpu_id: usize) -> (f32, f32) {
+        (
+            self.cpu_hardirq_rate.get(cpu_id).copied().unwrap_or(0.0),
+            self.cpu_softirq_rate.get(cpu_id).copied().unwrap_or(0.0),
+        )
+    }
+
+    /// IRQ lines that fired on a CPU, busiest first
+    pub fn lines_on(&self, cpu_id: usize) -> Vec<&IrqLine> {
+        let mut lines: Vec<&IrqLine> = self
+            .lines
+            .iter()
+            .filter(|l| l.rate_on(cpu_id) > 0.0)
+            .collect();
+        lines.sort_by(|a, b| {
+            b.rate_on(cpu_id)
+                .partial_cmp(&a.rate_on(cpu_id))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        lines
+    }
+}
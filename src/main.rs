@@ -1,50 +1,185 @@
 mod app;
+mod batch;
 mod config;
+mod db;
 mod event;
+mod export;
+mod irq;
+mod record;
+mod remote;
+mod settings;
 mod stats;
 mod topology;
 mod ui;
 
 use std::io;
+use std::thread;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use crossterm::{
-    execute,
+    event::{DisableMouseCapture, EnableMouseCapture, MouseButton, MouseEvent, MouseEventKind},
+    execute, style,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 
 use app::{App, ViewMode};
-use config::Config;
+use config::{Command, Config};
 use event::{handle_key, Event, EventHandler, KeyAction};
+use record::Replayer;
+use remote::{ClusterHost, RemoteSource};
 use topology::detect_topology;
+use ui::gradient::ColorDepth;
+use ui::hitmap::{HitMap, Target};
 use ui::{render, Theme};
 
 fn main() -> Result<()> {
     // Parse command line arguments
-    let config = Config::parse_args();
+    let config = Config::parse_args()?;
 
-    // Detect system topology
-    let topology = detect_topology().context("Failed to detect CPU topology")?;
+    // Sinks tag samples with this host, so they only make sense for local sampling
+    let sinks_requested = config.influx.is_some() || config.statsd.is_some() || config.db.is_some();
+    if config.command.is_some() && (sinks_requested || config.no_tui) {
+        bail!("--influx, --statsd, --db and --no-tui only apply to a local session");
+    }
+
+    // Sources wake the event loop when samples arrive
+    let mut event_handler = EventHandler::new();
+
+    // Create application state; replays and remote agents carry their own topology
+    let mut app = match &config.command {
+        Some(Command::IrqPlan { apply }) => {
+            let topology = detect_topology().context("Failed to detect CPU topology")?;
+            return irq::plan::run(&topology, *apply);
+        }
+        Some(Command::Topo { json }) => {
+            let topology = detect_topology().context("Failed to detect CPU topology")?;
+            return topology::report::run(&topology, *json);
+        }
+        Some(Command::Serve { listen }) => {
+            let topology = detect_topology().context("Failed to detect CPU topology")?;
+            return export::server::run(topology, *listen, config.source, config.refresh_rate);
+        }
+        Some(Command::Agent { listen }) => {
+            let topology = detect_topology().context("Failed to detect CPU topology")?;
+            return remote::agent::run(topology, *listen, config.source, config.refresh_rate);
+        }
+        Some(Command::Connect { addrs }) if addrs.len() == 1 => {
+            let (topology, stats, source) =
+                RemoteSource::connect(&addrs[0], event_handler.waker())?;
+            App::remote(topology, stats, source, &config)
+        }
+        Some(Command::Connect { addrs }) => {
+            // A few unreachable nodes should not keep the rest off screen, or
+            // delay startup by one connect timeout each
+            let results: Vec<Result<ClusterHost>> = thread::scope(|scope| {
+                let connects: Vec<_> = addrs
+                    .iter()
+                    .map(|addr| {
+                        let waker = event_handler.waker();
+                        scope.spawn(move || ClusterHost::connect(addr, config.history, waker))
+                    })
+                    .collect();
+                connects
+                    .into_iter()
+                    .map(|connect| {
+                        connect
+                            .join()
+                            .unwrap_or_else(|_| Err(anyhow!("connection thread panicked")))
+                    })
+                    .collect()
+            });
+            let mut hosts = Vec::new();
+            for (addr, result) in addrs.iter().zip(results) {
+                match result {
+                    Ok(host) => hosts.push(host),
+                    Err(e) => eprintln!("zentop: skipping {}: {:#}", addr, e),
+                }
+            }
+            if hosts.is_empty() {
+                bail!("No agents reachable");
+            }
+            App::cluster(hosts, &config)
+        }
+        Some(Command::Query {
+            db,
+            since,
+            group,
+            agg,
+        }) => return db::query::run(db, *since, *group, *agg),
+        Some(Command::Replay { file }) => App::replay(Replayer::open(file)?, &config),
+        None => {
+            let topology = detect_topology().context("Failed to detect CPU topology")?;
+            if config.batch {
+                return batch::run(&topology, &config);
+            }
+            let sinks = export::sinks_from_config(&config)?;
+            if config.no_tui {
+                if sinks.is_empty() {
+                    bail!("--no-tui needs --influx, --statsd or --db");
+                }
+                return export::run_sinks(&topology, sinks, config.source, config.refresh_rate);
+            }
+            let mut app = App::new(topology, &config, event_handler.waker());
+            app.add_sinks(sinks);
+            app
+        }
+    };
+
+    app.check_filters()?;
+    if let Some(path) = &config.record {
+        app.start_recording(path)?;
+    }
+
+    // Resolve themes before touching the terminal so errors print normally
+    let theme = Theme::load(&config.theme)?;
+    let mut themes = Theme::available();
+    let theme_idx = match themes.iter().position(|t| t.name == theme.name) {
+        Some(idx) => {
+            themes[idx] = theme;
+            idx
+        }
+        None => {
+            themes.push(theme);
+            themes.len() - 1
+        }
+    };
+    let color_depth = ColorDepth::detect();
+    for theme in &mut themes {
+        theme.color_depth = color_depth;
+        if let Some(gradient) = config.gradient {
+            theme.gradient = gradient;
+        }
+        if let Some(thresholds) = config.usage_thresholds {
+            theme.thresholds = thresholds;
+        }
+    }
+    // NO_COLOR already selected the mono theme; crossterm would also strip
+    // the colors of themes picked explicitly or with the cycle key
+    style::force_color_output(true);
 
     // Setup terminal
     enable_raw_mode().context("Failed to enable raw mode")?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen).context("Failed to enter alternate screen")?;
+    if config.mouse {
+        execute!(stdout, EnableMouseCapture).context("Failed to enable mouse capture")?;
+    }
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).context("Failed to create terminal")?;
 
-    // Create application state
-    let mut app = App::new(topology);
-    let theme = Theme::default();
-    let event_handler = EventHandler::new(config.refresh_rate);
+    event_handler.set_sample_interval(app.poll_interval());
 
     // Main loop
-    let result = run_app(&mut terminal, &mut app, &theme, &event_handler);
+    let result = run_app(&mut terminal, &mut app, &themes, theme_idx, &mut event_handler);
 
     // Restore terminal
     disable_raw_mode().context("Failed to disable raw mode")?;
+    if config.mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)
+            .context("Failed to disable mouse capture")?;
+    }
     execute!(terminal.backend_mut(), LeaveAlternateScreen)
         .context("Failed to leave alternate screen")?;
     terminal.show_cursor().context("Failed to show cursor")?;
@@ -55,39 +190,113 @@ fn main() -> Result<()> {
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
-    theme: &Theme,
-    event_handler: &EventHandler,
+    themes: &[Theme],
+    mut theme_idx: usize,
+    event_handler: &mut EventHandler,
 ) -> Result<()> {
+    // The startup theme may be a file path rather than a name, so keep its spec
+    let (startup_idx, startup_theme) = (theme_idx, app.theme.clone());
+    // Clickable regions of the last frame drawn
+    let mut hits = HitMap::default();
     loop {
-        // Render
-        terminal.draw(|frame| render(frame, app, theme))?;
-
-        // Handle events
         match event_handler.next()? {
             Event::Key(key) => {
                 if app.show_help {
-                    // Any key closes help
-                    app.toggle_help();
+                    // Navigation keys scroll help, any other key closes it
+                    let page = app.help_scroll.viewport.max(1) as isize;
+                    match handle_key(key) {
+                        KeyAction::CursorUp => app.scroll_help(-1),
+                        KeyAction::CursorDown => app.scroll_help(1),
+                        KeyAction::PageUp => app.scroll_help(-page),
+                        KeyAction::PageDown => app.scroll_help(page),
+                        KeyAction::ScrollTop => app.scroll_help(isize::MIN),
+                        KeyAction::ScrollBottom => app.scroll_help(isize::MAX),
+                        _ => app.toggle_help(),
+                    }
                 } else {
                     match handle_key(key) {
                         KeyAction::Quit => app.quit(),
                         KeyAction::ViewCore => app.set_view_mode(ViewMode::Core),
                         KeyAction::ViewCcd => app.set_view_mode(ViewMode::Ccd),
                         KeyAction::ViewNps => app.set_view_mode(ViewMode::Nps),
+                        KeyAction::ViewCgroup => app.set_view_mode(ViewMode::Cgroup),
+                        KeyAction::ViewIrq => app.set_view_mode(ViewMode::Irq),
+                        KeyAction::ViewCluster => app.set_view_mode(ViewMode::Cluster),
                         KeyAction::ToggleSmt => app.toggle_smt(),
+                        KeyAction::ToggleVcpus => app.toggle_vcpus(),
                         KeyAction::CycleDisplayMode => app.cycle_display_mode(),
+                        KeyAction::CycleTheme => {
+                            theme_idx = (theme_idx + 1) % themes.len();
+                            app.theme = if theme_idx == startup_idx {
+                                startup_theme.clone()
+                            } else {
+                                themes[theme_idx].name.clone()
+                            };
+                            app.set_notice(format!("Theme: {}", themes[theme_idx].name));
+                        }
                         KeyAction::ToggleHelp => app.toggle_help(),
-                        KeyAction::ScrollUp => app.scroll_up(),
-                        KeyAction::ScrollDown => app.scroll_down(),
+                        KeyAction::CursorUp => move_cursor_vertically(app, &hits, false),
+                        KeyAction::CursorDown => move_cursor_vertically(app, &hits, true),
+                        KeyAction::CursorLeft => {
+                            app.move_cursor(-1);
+                            follow_cursor(app, &hits);
+                        }
+                        KeyAction::CursorRight => {
+                            app.move_cursor(1);
+                            follow_cursor(app, &hits);
+                        }
+                        KeyAction::PageUp => move_by_page(app, &hits, false),
+                        KeyAction::PageDown => move_by_page(app, &hits, true),
+                        KeyAction::ScrollTop => move_to_edge(app, &hits, false),
+                        KeyAction::ScrollBottom => move_to_edge(app, &hits, true),
+                        KeyAction::ToggleChart if app.view_mode == ViewMode::Cluster => {
+                            app.focus_selected_host()
+                        }
+                        KeyAction::ToggleChart => app.toggle_chart(),
+                        KeyAction::ToggleDetail => app.toggle_detail(),
+                        KeyAction::TogglePause => app.toggle_pause(),
+                        KeyAction::Step => app.step()?,
+                        KeyAction::SeekBackward => app.seek_backward(),
+                        KeyAction::SeekForward => app.seek_forward(),
+                        KeyAction::Faster => app.faster(),
+                        KeyAction::Slower => app.slower(),
+                        KeyAction::SaveSettings => app.save_settings(),
                         KeyAction::None => {}
                     }
+                    event_handler.set_sample_interval(app.poll_interval());
+                }
+            }
+            Event::Mouse(mouse) => {
+                if app.show_help {
+                    // The wheel scrolls help, any click closes it
+                    match mouse.kind {
+                        MouseEventKind::ScrollUp => app.scroll_help(-WHEEL_LINES),
+                        MouseEventKind::ScrollDown => app.scroll_help(WHEEL_LINES),
+                        MouseEventKind::Down(_) => app.toggle_help(),
+                        _ => {}
+                    }
+                } else {
+                    handle_mouse(app, &hits, mouse);
                 }
             }
-            Event::Tick => {
-                app.refresh_stats();
+            Event::Sample => {
+                app.refresh_stats()?;
+            }
+            Event::Redraw => {
+                hits = HitMap::default();
+                terminal.draw(|frame| render(frame, app, &themes[theme_idx], &hits))?;
+                // Keep scroll keys within the content the views just drew
+                for pane in 0..app.scroll.len() {
+                    if let Some(scroll) = hits.scroll_of(pane) {
+                        app.scroll[pane] = scroll;
+                    }
+                }
+                if let Some(scroll) = hits.help_scroll_of() {
+                    app.help_scroll = scroll;
+                }
             }
             Event::Resize(_, _) => {
-                // Terminal will handle resize automatically
+                // Terminal will handle resize automatically; the redraw follows
             }
         }
 
@@ -98,3 +307,129 @@ fn run_app(
 
     Ok(())
 }
+
+/// Lines scrolled per mouse wheel notch
+const WHEEL_LINES: isize = 3;
+
+/// Apply a click or wheel event to whatever the last frame drew under the pointer
+fn handle_mouse(app: &mut App, hits: &HitMap, mouse: MouseEvent) {
+    let targets = hits.targets_at(mouse.column, mouse.row);
+
+    // Clicking or scrolling anywhere in a socket pane focuses it
+    let socket = targets.iter().find_map(|target| match target {
+        Target::Socket(socket) => Some(*socket),
+        _ => None,
+    });
+    if let Some(socket) = socket {
+        app.focus_socket(socket);
+    }
+
+    match mouse.kind {
+        MouseEventKind::ScrollUp => app.scroll_by(-WHEEL_LINES),
+        MouseEventKind::ScrollDown => app.scroll_by(WHEEL_LINES),
+        MouseEventKind::Down(MouseButton::Left) => match targets.first() {
+            Some(Target::Cpu(cpu_id)) => app.select_cpu(*cpu_id),
+            Some(Target::Group(group_id)) => app.select_group(*group_id),
+            Some(Target::Host(idx)) => app.select_host(*idx),
+            Some(Target::ViewLabel) => app.cycle_view_mode(),
+            Some(Target::SmtLabel) => app.toggle_smt(),
+            Some(Target::ModeLabel) => app.cycle_display_mode(),
+            Some(Target::Socket(_)) | None => {}
+        },
+        _ => {}
+    }
+}
+
+/// What the cursor sits on: the selected host in the cluster overview, the
+/// selected group where the view draws no CPUs, otherwise the selected CPU
+fn cursor_target(app: &App) -> Target {
+    if app.view_mode == ViewMode::Cluster {
+        return Target::Host(app.selected_host);
+    }
+    match app.selected_group_id().filter(|_| app.cursor_on_groups()) {
+        Some(group_id) => Target::Group(group_id),
+        None => Target::Cpu(app.selected_cpu),
+    }
+}
+
+/// Move the cursor to the row above or below, or scroll views without a cursor
+///
+/// The last frame decides what is above or below, so the cursor follows
+/// grid columns and group boundaries in every display mode. Past the edge
+/// of the screen it moves by a full row in display order instead.
+fn move_cursor_vertically(app: &mut App, hits: &HitMap, down: bool) {
+    if !app.has_cursor() {
+        if down {
+            app.scroll_down();
+        } else {
+            app.scroll_up();
+        }
+        return;
+    }
+
+    let from = cursor_target(app);
+    match hits.neighbor(from, down) {
+        Some(target) => select_target(app, target),
+        None => {
+            let row = hits.row_len(from).max(1) as isize;
+            app.move_cursor(if down { row } else { -row });
+        }
+    }
+    follow_cursor(app, hits);
+}
+
+/// Move the cursor a page up or down, or scroll a page in views without one
+fn move_by_page(app: &mut App, hits: &HitMap, down: bool) {
+    let page = app.page_size() as isize;
+    let lines = if down { page } else { -page };
+    if app.view_mode == ViewMode::Cluster {
+        app.move_cursor(lines);
+        follow_cursor(app, hits);
+    } else if !app.has_cursor() {
+        app.scroll_by(lines);
+    } else if let Some(target) = hits.shifted(cursor_target(app), lines) {
+        select_target(app, target);
+        follow_cursor(app, hits);
+    }
+}
+
+/// Move the cursor to the first or last item of its pane and scroll there,
+/// or just scroll in views without a cursor
+fn move_to_edge(app: &mut App, hits: &HitMap, end: bool) {
+    if app.view_mode == ViewMode::Cluster {
+        app.move_cursor(if end { isize::MAX } else { isize::MIN });
+        follow_cursor(app, hits);
+    } else if !app.has_cursor() {
+        app.scroll_to_edge(app.scroll_pane(), end);
+    } else if let Some(target) = hits.edge(cursor_target(app), end) {
+        select_target(app, target);
+        follow_cursor(app, hits);
+        // Also reveal group headers above the first item
+        if let Some(placement) = hits.placement(target) {
+            app.scroll_to_edge(placement.pane, end);
+        }
+    }
+}
+
+/// Select a CPU or group the cursor moved onto
+fn select_target(app: &mut App, target: Target) {
+    match target {
+        Target::Cpu(cpu_id) => app.select_cpu(cpu_id),
+        Target::Group(group_id) => app.select_group(group_id),
+        _ => {}
+    }
+}
+
+/// Scroll so the selection is on screen after the cursor moved, and give
+/// its pane focus
+fn follow_cursor(app: &mut App, hits: &HitMap) {
+    if let Some(placement) = hits.placement(cursor_target(app)) {
+        app.focus_socket(placement.pane);
+        app.scroll_into_view(
+            placement.pane,
+            placement.line,
+            placement.height,
+            placement.viewport,
+        );
+    }
+}
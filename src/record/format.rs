@@ -0,0 +1,175 @@
+use std::io::Read;
+
+use anyhow::{bail, Context, Result};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+use crate::stats::SystemStats;
+use crate::topology::ZenTopology;
+
+/// Magic bytes at the start of a recording
+pub const MAGIC: &[u8; 4] = b"ZTRC";
+
+/// Recording format version, bumped on incompatible changes
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Largest topology or sample accepted when decoding, so a corrupt file or
+/// a misbehaving agent cannot make us allocate without bound
+const MAX_FRAME: u64 = 64 * 1024 * 1024;
+
+/// A recorded snapshot
+///
+/// A recording is `MAGIC`, `FORMAT_VERSION`, the bincode-encoded
+/// `ZenTopology`, then a sequence of bincode-encoded `RecordedSample`s
+/// until end of file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedSample {
+    /// Milliseconds since the recording started
+    pub elapsed_ms: u64,
+    /// Wall-clock time in milliseconds since the Unix epoch
+    pub unix_ms: u64,
+    pub stats: SystemStats,
+}
+
+/// Read and validate the header, returning the recorded topology
+pub fn read_header<R: Read>(reader: &mut R) -> Result<ZenTopology> {
+    let mut magic = [0u8; 4];
+    reader
+        .read_exact(&mut magic)
+        .context("Failed to read recording header")?;
+    if &magic != MAGIC {
+        bail!("Not a zentop recording");
+    }
+
+    let mut version = [0u8; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != FORMAT_VERSION {
+        bail!(
+            "Unsupported recording version {} (expected {})",
+            version,
+            FORMAT_VERSION
+        );
+    }
+
+    decoder()
+        .deserialize_from(reader)
+        .context("Failed to read recorded topology")
+}
+
+/// Read the next sample, or `None` at a clean end of stream
+pub fn read_sample<R: Read>(reader: &mut R) -> Result<Option<RecordedSample>> {
+    match decoder().deserialize_from(reader) {
+        Ok(sample) => Ok(Some(sample)),
+        Err(e) => match *e {
+            bincode::ErrorKind::Io(ref io) if io.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Ok(None)
+            }
+            _ => Err(e).context("Failed to read recorded sample"),
+        },
+    }
+}
+
+/// Bincode options matching `bincode::serialize`, with a size limit
+fn decoder() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(MAX_FRAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::record::Recorder;
+    use crate::stats::CoreUsage;
+
+    fn stats(usages: &[(usize, f32)]) -> SystemStats {
+        let core_usages = usages
+            .iter()
+            .map(|&(core_id, usage_percent)| CoreUsage {
+                core_id,
+                usage_percent,
+                steal_percent: 0.5,
+                guest_percent: 0.0,
+                frequency_mhz: Some(3700),
+            })
+            .collect();
+        SystemStats::from_core_usages(core_usages, std::time::Instant::now())
+    }
+
+    fn recording(samples: &[SystemStats]) -> Vec<u8> {
+        let topology = ZenTopology {
+            cpu_model: String::from("AMD EPYC 9654 96-Core Processor"),
+            total_threads: 2,
+            ..ZenTopology::default()
+        };
+        let mut bytes = Vec::new();
+        let mut recorder = Recorder::new(&mut bytes, &topology).unwrap();
+        for sample in samples {
+            recorder.write(sample).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn round_trips_topology_and_samples() {
+        let bytes = recording(&[stats(&[(0, 10.0), (2, 90.0)]), stats(&[(0, 20.0)])]);
+        let mut reader = Cursor::new(bytes);
+
+        let topology = read_header(&mut reader).unwrap();
+        assert_eq!(topology.cpu_model, "AMD EPYC 9654 96-Core Processor");
+        assert_eq!(topology.total_threads, 2);
+
+        let first = read_sample(&mut reader).unwrap().unwrap();
+        assert!(first.unix_ms > 0);
+        let ids: Vec<usize> = first.stats.core_usages.iter().map(|u| u.core_id).collect();
+        assert_eq!(ids, vec![0, 2]);
+        assert_eq!(first.stats.usage(2).unwrap().usage_percent, 90.0);
+        assert_eq!(first.stats.total_usage, 50.0);
+
+        let second = read_sample(&mut reader).unwrap().unwrap();
+        assert!(second.elapsed_ms >= first.elapsed_ms);
+        assert_eq!(second.stats.core_usages.len(), 1);
+
+        assert!(read_sample(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_foreign_files() {
+        let err = read_header(&mut Cursor::new(b"PK\x03\x04rest".to_vec())).unwrap_err();
+        assert!(err.to_string().contains("Not a zentop recording"));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bytes = recording(&[]);
+        bytes[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        let err = read_header(&mut Cursor::new(bytes)).unwrap_err();
+        assert!(err.to_string().contains("Unsupported recording version"));
+    }
+
+    #[test]
+    fn truncated_last_sample_ends_the_recording() {
+        // What a recorder killed mid-write leaves behind
+        let bytes = recording(&[stats(&[(0, 10.0)]), stats(&[(0, 20.0)])]);
+        let mut reader = Cursor::new(&bytes[..bytes.len() - 3]);
+        read_header(&mut reader).unwrap();
+        assert!(read_sample(&mut reader).unwrap().is_some());
+        assert!(read_sample(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn oversized_frames_are_rejected() {
+        // A CPU model name claiming to be 16 EiB long must fail before any
+        // allocation rather than after reading to the end of the stream
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        bytes.extend_from_slice(b"AMD EPYC");
+        let err = read_header(&mut Cursor::new(bytes)).unwrap_err();
+        assert!(format!("{:#}", err).contains("limit"), "{:#}", err);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod format;
+pub mod recorder;
+pub mod replay;
+
+pub use recorder::Recorder;
+pub use replay::Replayer;
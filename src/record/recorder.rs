@@ -0,0 +1,58 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+
+use super::format::{RecordedSample, FORMAT_VERSION, MAGIC};
+use crate::stats::SystemStats;
+use crate::topology::ZenTopology;
+
+/// Writes timestamped snapshots to a recording
+pub struct Recorder<W: Write = BufWriter<File>> {
+    writer: W,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Create a recording file and write the header and topology
+    pub fn create(path: &Path, topology: &ZenTopology) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+        Recorder::new(BufWriter::new(file), topology)
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Write the header and topology to any writer (file or socket)
+    pub fn new(mut writer: W, topology: &ZenTopology) -> Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, topology).context("Failed to write topology")?;
+        writer.flush()?;
+
+        Ok(Self {
+            writer,
+            started: Instant::now(),
+        })
+    }
+
+    /// Append a snapshot, flushing so a crash loses at most one sample
+    pub fn write(&mut self, stats: &SystemStats) -> Result<()> {
+        let sample = RecordedSample {
+            elapsed_ms: stats
+                .timestamp
+                .saturating_duration_since(self.started)
+                .as_millis() as u64,
+            unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            stats: stats.clone(),
+        };
+        bincode::serialize_into(&mut self.writer, &sample).context("Failed to write sample")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+
+use super::format::{read_header, read_sample, RecordedSample};
+use crate::stats::{Playback, StatsSource, SystemStats};
+use crate::topology::ZenTopology;
+
+/// Playback speeds selectable with faster/slower
+const SPEEDS: [f64; 9] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0];
+
+/// Samples skipped by a single seek
+const SEEK_STEP: usize = 10;
+
+/// Plays back a recording in place of live sampling
+pub struct Replayer {
+    topology: ZenTopology,
+    samples: Vec<RecordedSample>,
+    /// Index of the sample currently shown
+    position: usize,
+    /// Playback time in recording milliseconds
+    playhead_ms: f64,
+    speed_index: usize,
+    paused: bool,
+    last_tick: Instant,
+    /// Instant mapped to the recording's elapsed time zero
+    base: Instant,
+}
+
+impl Replayer {
+    /// Load a recording into memory
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open recording {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+
+        let topology = read_header(&mut reader)?;
+        let mut samples = Vec::new();
+        while let Some(sample) = read_sample(&mut reader)? {
+            samples.push(sample);
+        }
+        if samples.is_empty() {
+            bail!("Recording {} contains no samples", path.display());
+        }
+
+        let playhead_ms = samples[0].elapsed_ms as f64;
+        Ok(Self {
+            topology,
+            samples,
+            position: 0,
+            playhead_ms,
+            speed_index: 2,
+            paused: false,
+            last_tick: Instant::now(),
+            base: Instant::now(),
+        })
+    }
+
+    pub fn topology(&self) -> &ZenTopology {
+        &self.topology
+    }
+
+    /// Snapshot at the current position
+    pub fn current(&self) -> SystemStats {
+        self.stats_at(self.position)
+    }
+
+    pub fn speed(&self) -> f64 {
+        SPEEDS[self.speed_index]
+    }
+
+    fn jump_to(&mut self, position: usize) {
+        self.position = position;
+        self.playhead_ms = self.samples[position].elapsed_ms as f64;
+        self.last_tick = Instant::now();
+    }
+
+    fn stats_at(&self, idx: usize) -> SystemStats {
+        let sample = &self.samples[idx];
+        let mut stats = sample.stats.clone();
+        stats.timestamp = self.base + Duration::from_millis(sample.elapsed_ms);
+        if let Some(previous) = idx.checked_sub(1).map(|i| &self.samples[i]) {
+            stats.interval =
+                Duration::from_millis(sample.elapsed_ms.saturating_sub(previous.elapsed_ms));
+        }
+        stats
+    }
+}
+
+impl StatsSource for Replayer {
+    /// Advance the playhead by wall time elapsed since the last call
+    ///
+    /// Returns every sample passed, oldest first, so history stays complete
+    /// at speeds above 1x.
+    fn poll(&mut self) -> Vec<SystemStats> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_tick);
+        self.last_tick = now;
+
+        if self.paused {
+            return Vec::new();
+        }
+
+        self.playhead_ms += elapsed.as_secs_f64() * 1000.0 * self.speed();
+
+        let start = self.position;
+        while self.position + 1 < self.samples.len()
+            && self.samples[self.position + 1].elapsed_ms as f64 <= self.playhead_ms
+        {
+            self.position += 1;
+        }
+
+        // Stop at the end of the recording
+        if self.position + 1 == self.samples.len() {
+            self.paused = true;
+        }
+
+        (start + 1..=self.position)
+            .map(|idx| self.stats_at(idx))
+            .collect()
+    }
+
+    /// Status text, e.g. "REPLAY 2024-05-01 14:03:07 UTC 00:01:23/00:10:00 2x PAUSED"
+    fn status(&self) -> Option<String> {
+        let total = self.samples.last().map_or(0, |s| s.elapsed_ms);
+        let sample = &self.samples[self.position];
+        Some(format!(
+            "REPLAY {} {}/{} {}x{}",
+            format_utc(sample.unix_ms),
+            format_hms(sample.elapsed_ms),
+            format_hms(total),
+            self.speed(),
+            if self.paused { " PAUSED" } else { "" }
+        ))
+    }
+
+    fn playback(&mut self) -> Option<&mut dyn Playback> {
+        Some(self)
+    }
+}
+
+impl Playback for Replayer {
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.last_tick = Instant::now();
+    }
+
+    fn step(&mut self) -> Option<SystemStats> {
+        self.paused = true;
+        if self.position + 1 >= self.samples.len() {
+            return None;
+        }
+        self.jump_to(self.position + 1);
+        Some(self.current())
+    }
+
+    /// Jump backward by one seek step
+    fn seek_backward(&mut self) {
+        self.jump_to(self.position.saturating_sub(SEEK_STEP));
+    }
+
+    /// Jump forward by one seek step
+    fn seek_forward(&mut self) {
+        self.jump_to((self.position + SEEK_STEP).min(self.samples.len() - 1));
+    }
+
+    fn faster(&mut self) {
+        self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1);
+    }
+
+    fn slower(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    fn window(&self, count: usize) -> Vec<SystemStats> {
+        let start = (self.position + 1).saturating_sub(count);
+        (start..=self.position)
+            .map(|idx| self.stats_at(idx))
+            .collect()
+    }
+}
+
+fn format_hms(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// "YYYY-MM-DD HH:MM:SS UTC" for milliseconds since the Unix epoch
+fn format_utc(unix_ms: u64) -> String {
+    let secs = unix_ms / 1000;
+    let days = (secs / 86400) as i64;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_wall_clock_time_in_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_utc(951_782_400_000), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_utc(1_714_572_187_999), "2024-05-01 14:03:07 UTC");
+        assert_eq!(format_utc(4_107_542_399_000), "2100-02-28 23:59:59 UTC");
+    }
+
+    #[test]
+    fn formats_elapsed_time() {
+        assert_eq!(format_hms(0), "00:00:00");
+        assert_eq!(format_hms(83_999), "00:01:23");
+        assert_eq!(format_hms(90_061_000), "25:01:01");
+    }
+}
@@ -0,0 +1,98 @@
+use std::io::{BufWriter, Read};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::config::SourceKind;
+use crate::record::Recorder;
+use crate::stats::sampler;
+use crate::topology::ZenTopology;
+
+/// Clients served at once; each has its own sampler, so further ones are
+/// turned away
+const MAX_CLIENTS: usize = 16;
+
+/// Sampling periods a client may request, in milliseconds
+const MIN_INTERVAL_MS: u64 = 100;
+const MAX_INTERVAL_MS: u64 = 10_000;
+
+/// Stream topology and stats to every client that connects
+///
+/// The wire format is the recording format: the header and topology once,
+/// then one sample per refresh interval until the client hangs up. A client
+/// may change its refresh interval at any time by sending the new period in
+/// milliseconds as a little-endian u64.
+pub fn run(
+    topology: ZenTopology,
+    listen: SocketAddr,
+    source: SourceKind,
+    refresh_rate_ms: u64,
+) -> Result<()> {
+    let listener =
+        TcpListener::bind(listen).with_context(|| format!("Failed to listen on {}", listen))?;
+    eprintln!("zentop: agent listening on {}", listen);
+
+    let interval = Duration::from_millis(refresh_rate_ms);
+    let clients = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let peer = stream.peer_addr().ok();
+        if clients.fetch_add(1, Ordering::Relaxed) >= MAX_CLIENTS {
+            clients.fetch_sub(1, Ordering::Relaxed);
+            if let Some(peer) = peer {
+                eprintln!("zentop: refusing client {}: {} already connected", peer, MAX_CLIENTS);
+            }
+            continue;
+        }
+        let topology = topology.clone();
+        let clients = Arc::clone(&clients);
+        thread::spawn(move || {
+            if let Err(e) = stream_stats(stream, &topology, source, interval) {
+                if let Some(peer) = peer {
+                    eprintln!("zentop: client {} disconnected: {:#}", peer, e);
+                }
+            }
+            clients.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    Ok(())
+}
+
+fn stream_stats(
+    stream: TcpStream,
+    topology: &ZenTopology,
+    source: SourceKind,
+    interval: Duration,
+) -> Result<()> {
+    stream.set_nodelay(true)?;
+
+    // Interval changes arrive on the same connection; the reader ends when
+    // the client hangs up, and the next write notices
+    let interval_ms = Arc::new(AtomicU64::new(interval.as_millis() as u64));
+    {
+        let mut control = stream.try_clone()?;
+        let interval_ms = Arc::clone(&interval_ms);
+        thread::spawn(move || {
+            let mut request = [0u8; 8];
+            while control.read_exact(&mut request).is_ok() {
+                let ms = u64::from_le_bytes(request).clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS);
+                interval_ms.store(ms, Ordering::Relaxed);
+            }
+        });
+    }
+
+    let mut recorder = Recorder::new(BufWriter::new(stream), topology)?;
+    let mut sampler = sampler(source, topology);
+
+    loop {
+        recorder.write(&sampler.sample())?;
+        thread::sleep(Duration::from_millis(interval_ms.load(Ordering::Relaxed)));
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::{BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::event::Waker;
+use crate::record::format::{read_header, read_sample, RecordedSample};
+use crate::stats::{StatsSource, SystemStats};
+use crate::topology::ZenTopology;
+
+/// How long to wait for an agent to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for the topology and first snapshot, and the least
+/// silence after which an agent counts as gone
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Missed refresh intervals after which an agent counts as gone
+const STALE_INTERVALS: u32 = 3;
+
+/// Delay before the first reconnect attempt, doubled after every failure
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest delay between reconnect attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// What the reader thread hands to the event loop
+enum Update {
+    Stats(SystemStats),
+    /// The connection dropped; the thread keeps trying to reconnect
+    Lost,
+}
+
+/// Refresh interval requested from the agent, and the connection to send
+/// it on
+#[derive(Default)]
+struct Control {
+    stream: Option<TcpStream>,
+    interval: Option<Duration>,
+}
+
+impl Control {
+    /// Ask the agent for the requested interval, if any; a failed write
+    /// shows up as a read error on the stream
+    fn send(&mut self) {
+        if let (Some(stream), Some(interval)) = (&mut self.stream, self.interval) {
+            let _ = stream.write_all(&(interval.as_millis() as u64).to_le_bytes());
+        }
+    }
+}
+
+/// Snapshots streamed from a `zentop agent`
+pub struct RemoteSource {
+    addr: String,
+    receiver: Receiver<Update>,
+    connected: bool,
+    control: Arc<Mutex<Control>>,
+}
+
+impl RemoteSource {
+    /// Connect to an agent and read its topology and first snapshot; `waker`
+    /// is woken for every later snapshot
+    ///
+    /// An agent that goes away later is reconnected with exponential
+    /// backoff. The host behind the address is assumed unchanged, so the
+    /// topology is only read from the first connection.
+    pub fn connect(addr: &str, waker: Waker) -> Result<(ZenTopology, SystemStats, Self)> {
+        let (topology, first, mut reader) = open(addr)?;
+        let control = Arc::new(Mutex::new(Control {
+            stream: reader.get_ref().try_clone().ok(),
+            interval: None,
+        }));
+
+        // Reads block, so they live on their own thread. A read timeout or a
+        // closed connection marks the source disconnected until a
+        // reconnect succeeds.
+        let (sender, receiver) = mpsc::channel();
+        let thread_addr = addr.to_string();
+        let thread_control = Arc::clone(&control);
+        let mut previous_ms = first.elapsed_ms;
+        thread::spawn(move || loop {
+            if !forward(&mut reader, previous_ms, &sender, &waker) {
+                return;
+            }
+            // Let the header show the disconnect
+            if sender.send(Update::Lost).is_err() {
+                return;
+            }
+            waker.wake();
+
+            let (sample, next) = reconnect(&thread_addr);
+            reader = next;
+            // A restarted agent samples at its own rate until asked again
+            {
+                let mut control = thread_control.lock().unwrap();
+                control.stream = reader.get_ref().try_clone().ok();
+                control.send();
+            }
+            // The agent may have restarted its clock, so the gap has no interval
+            previous_ms = sample.elapsed_ms;
+            let mut stats = sample.stats;
+            stats.interval = Duration::ZERO;
+            if sender.send(Update::Stats(stats)).is_err() {
+                return;
+            }
+            waker.wake();
+        });
+
+        let source = Self {
+            addr: addr.to_string(),
+            receiver,
+            connected: true,
+            control,
+        };
+        Ok((topology, first.stats, source))
+    }
+
+    /// Address the agent was reached at
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+}
+
+/// Connect to an agent, trying each address it resolves to, and read its
+/// topology and first snapshot
+fn open(addr: &str) -> Result<(ZenTopology, RecordedSample, BufReader<TcpStream>)> {
+    let mut last_error = anyhow!("{} resolves to no address", addr);
+    let mut stream = None;
+    for socket_addr in addr
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve {}", addr))?
+    {
+        match TcpStream::connect_timeout(&socket_addr, CONNECT_TIMEOUT) {
+            Ok(connected) => {
+                stream = Some(connected);
+                break;
+            }
+            Err(e) => last_error = e.into(),
+        }
+    }
+    let stream = stream
+        .ok_or(last_error)
+        .with_context(|| format!("Failed to connect to {}", addr))?;
+    // An agent that dies without closing the connection would otherwise
+    // block the reader forever
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+
+    let topology =
+        read_header(&mut reader).with_context(|| format!("{} is not a zentop agent", addr))?;
+    let first =
+        read_sample(&mut reader)?.with_context(|| format!("{} closed the connection", addr))?;
+    Ok((topology, first, reader))
+}
+
+/// Retry `open` with exponential backoff until the agent is back
+fn reconnect(addr: &str) -> (RecordedSample, BufReader<TcpStream>) {
+    let mut delay = RECONNECT_DELAY;
+    loop {
+        thread::sleep(delay);
+        if let Ok((_, sample, reader)) = open(addr) {
+            return (sample, reader);
+        }
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+/// Pass snapshots on until the connection fails; false once nobody is
+/// listening any more
+fn forward(
+    reader: &mut BufReader<TcpStream>,
+    mut previous_ms: u64,
+    sender: &Sender<Update>,
+    waker: &Waker,
+) -> bool {
+    while let Ok(Some(sample)) = read_sample(reader) {
+        let mut stats = sample.stats;
+        stats.interval = Duration::from_millis(sample.elapsed_ms.saturating_sub(previous_ms));
+        previous_ms = sample.elapsed_ms;
+        // Allow a few missed samples at whatever rate the agent runs
+        let timeout = READ_TIMEOUT.max(stats.interval * STALE_INTERVALS);
+        if reader.get_ref().set_read_timeout(Some(timeout)).is_err() {
+            break;
+        }
+        if sender.send(Update::Stats(stats)).is_err() {
+            return false;
+        }
+        waker.wake();
+    }
+    true
+}
+
+impl StatsSource for RemoteSource {
+    fn poll(&mut self) -> Vec<SystemStats> {
+        let mut samples = Vec::new();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Update::Stats(stats)) => {
+                    self.connected = true;
+                    samples.push(stats);
+                }
+                Ok(Update::Lost) => self.connected = false,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.connected = false;
+                    break;
+                }
+            }
+        }
+        samples
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn wakes(&self) -> bool {
+        true
+    }
+
+    /// Ask the agent to sample at a different rate
+    fn set_interval(&mut self, interval: Duration) {
+        let mut control = self.control.lock().unwrap();
+        control.interval = Some(interval);
+        control.send();
+    }
+
+    /// Status text, e.g. "REMOTE node01:9865" or "... DISCONNECTED"
+    fn status(&self) -> Option<String> {
+        Some(format!(
+            "REMOTE {}{}",
+            self.addr,
+            if self.connected { "" } else { " DISCONNECTED" }
+        ))
+    }
+}
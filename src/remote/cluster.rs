@@ -0,0 +1,45 @@
+use anyhow::Result;
+
+use super::client::RemoteSource;
+use crate::event::Waker;
+use crate::stats::{History, SystemStats};
+use crate::topology::ZenTopology;
+
+/// One host of a multi-host session
+pub struct ClusterHost {
+    pub source: RemoteSource,
+    pub topology: ZenTopology,
+    pub stats: SystemStats,
+    pub history: History,
+}
+
+impl ClusterHost {
+    /// Connect to an agent, keeping `history_depth` samples while in the background
+    pub fn connect(addr: &str, history_depth: usize, waker: Waker) -> Result<Self> {
+        let (topology, stats, source) = RemoteSource::connect(addr, waker)?;
+        let mut history = History::new(history_depth);
+        history.push(&stats, &topology);
+        Ok(Self {
+            source,
+            topology,
+            stats,
+            history,
+        })
+    }
+
+    /// Apply snapshots received while another host is on screen
+    pub fn push(&mut self, samples: Vec<SystemStats>) {
+        for stats in samples {
+            self.history.push(&stats, &self.topology);
+            self.stats = stats;
+        }
+    }
+}
+
+/// Borrowed per-host data for the cluster overview
+pub struct HostView<'a> {
+    pub name: &'a str,
+    pub connected: bool,
+    pub topology: &'a ZenTopology,
+    pub stats: &'a SystemStats,
+}
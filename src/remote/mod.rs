@@ -0,0 +1,6 @@
+pub mod agent;
+pub mod client;
+pub mod cluster;
+
+pub use client::RemoteSource;
+pub use cluster::{ClusterHost, HostView};
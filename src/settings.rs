@@ -0,0 +1,157 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{DisplayMode, ViewMode};
+use crate::config::{Collectors, Columns, Config, SourceKind};
+use crate::ui::gradient::Gradient;
+
+/// Preferences persisted in the TOML configuration file
+///
+/// Every field is optional: unset fields keep the built-in default, and
+/// command-line flags override whatever the file says.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Refresh rate in milliseconds
+    pub refresh_rate: Option<u64>,
+    pub source: Option<SourceKind>,
+    /// Samples kept for sparklines and history charts
+    pub history: Option<usize>,
+    pub view: Option<ViewMode>,
+    pub display: Option<DisplayMode>,
+    pub show_smt: Option<bool>,
+    pub mouse: Option<bool>,
+    pub theme: Option<String>,
+    pub gradient: Option<Gradient>,
+    #[serde(skip_serializing_if = "Thresholds::is_empty")]
+    pub thresholds: Thresholds,
+    pub collectors: Option<Collectors>,
+    pub columns: Option<Columns>,
+}
+
+/// `[thresholds]` table
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    /// Aggregate steal percentage that triggers the header warning
+    pub steal: Option<f32>,
+    /// Usage percentages where bars switch to the medium, high and critical colors
+    pub usage: Option<[f32; 3]>,
+}
+
+impl Thresholds {
+    fn is_empty(&self) -> bool {
+        self.steal.is_none() && self.usage.is_none()
+    }
+}
+
+impl Settings {
+    /// `$XDG_CONFIG_HOME/zentop`, falling back to `~/.config/zentop`
+    fn config_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(base.join("zentop"))
+    }
+
+    /// `config.toml` in the config directory
+    pub fn default_path() -> Option<PathBuf> {
+        Self::config_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    /// Directory searched for `<name>.toml` theme files
+    pub fn themes_dir() -> Option<PathBuf> {
+        Self::config_dir().map(|dir| dir.join("themes"))
+    }
+
+    /// Read a settings file; a missing file yields the defaults
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let settings: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+        if settings.refresh_rate == Some(0) {
+            bail!("Invalid config file {}: refresh_rate must be at least 1", path.display());
+        }
+        if let Some(usage) = settings.thresholds.usage {
+            if !usage.iter().all(|t| (0.0..=100.0).contains(t))
+                || !usage.windows(2).all(|w| w[0] <= w[1])
+            {
+                bail!(
+                    "Invalid config file {}: usage thresholds must be ascending percentages",
+                    path.display()
+                );
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Write the settings, creating the config directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let content = toml::to_string_pretty(self)?;
+        fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Fill `config` from the file wherever the command line was silent
+    pub fn apply(&self, config: &mut Config, matches: &ArgMatches) {
+        let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+
+        if let Some(refresh_rate) = self.refresh_rate.filter(|_| !from_cli("refresh_rate")) {
+            config.refresh_rate = refresh_rate;
+        }
+        if let Some(source) = self.source.filter(|_| !from_cli("source")) {
+            config.source = source;
+        }
+        if let Some(history) = self.history.filter(|_| !from_cli("history")) {
+            config.history = history;
+        }
+        if let Some(steal) = self
+            .thresholds
+            .steal
+            .filter(|_| !from_cli("steal_threshold"))
+        {
+            config.steal_threshold = steal;
+        }
+        if let Some(view) = self.view.filter(|_| !from_cli("view")) {
+            config.view = view;
+        }
+        if let Some(display) = self.display.filter(|_| !from_cli("display")) {
+            config.display = display;
+        }
+        if let Some(show_smt) = self.show_smt.filter(|_| !from_cli("show_smt")) {
+            config.show_smt = show_smt;
+        }
+        if let Some(mouse) = self.mouse.filter(|_| !from_cli("mouse")) {
+            config.mouse = mouse;
+        }
+        if let Some(theme) = self.theme.as_ref().filter(|_| !from_cli("theme")) {
+            config.theme = theme.clone();
+        }
+        if let Some(gradient) = self.gradient.filter(|_| !from_cli("gradient")) {
+            config.gradient = Some(gradient);
+        }
+        if let Some(usage) = self.thresholds.usage {
+            config.usage_thresholds = Some(usage);
+        }
+        if let Some(collectors) = self.collectors {
+            config.collectors = collectors;
+        }
+        if let Some(columns) = self.columns {
+            config.columns = columns;
+        }
+    }
+}
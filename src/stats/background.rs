@@ -0,0 +1,126 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::event::Waker;
+
+/// Runs a collector on its own thread and delivers results over a channel
+///
+/// Slow collectors (sysfs walks, hwmon, /proc scans) never block the UI
+/// thread; it only drains whatever has arrived since the last tick.
+pub struct Background<T> {
+    receiver: Receiver<T>,
+    active: Arc<AtomicBool>,
+    interval_ms: Arc<AtomicU64>,
+    /// Cuts the worker's sleep short so setting changes apply at once
+    wake: Sender<()>,
+}
+
+impl<T: Send + 'static> Background<T> {
+    /// Spawn a worker; `make` runs on the new thread and returns the collector
+    ///
+    /// Construction happens off the caller's thread too, so collectors that
+    /// need a warm-up delay or an expensive scan do not hold up startup.
+    pub fn spawn<M, C>(make: M, interval: Duration, active: bool) -> Self
+    where
+        M: FnOnce() -> C + Send + 'static,
+        C: FnMut() -> T,
+    {
+        Self::start(make, interval, active, None)
+    }
+
+    /// Like `spawn`, and wake the event loop whenever a result is delivered
+    pub fn spawn_waking<M, C>(make: M, interval: Duration, active: bool, waker: Waker) -> Self
+    where
+        M: FnOnce() -> C + Send + 'static,
+        C: FnMut() -> T,
+    {
+        Self::start(make, interval, active, Some(waker))
+    }
+
+    fn start<M, C>(make: M, interval: Duration, active: bool, waker: Option<Waker>) -> Self
+    where
+        M: FnOnce() -> C + Send + 'static,
+        C: FnMut() -> T,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let active = Arc::new(AtomicBool::new(active));
+        let flag = Arc::clone(&active);
+        let interval_ms = Arc::new(AtomicU64::new(interval.as_millis() as u64));
+        let period = Arc::clone(&interval_ms);
+        let (wake, wakeups) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut collect = make();
+            let mut was_active = flag.load(Ordering::Relaxed);
+            loop {
+                let started = Instant::now();
+                let is_active = flag.load(Ordering::Relaxed);
+                if is_active {
+                    let result = collect();
+                    // After being idle the first result spans the whole gap,
+                    // so it only primes the collector's counters
+                    if was_active {
+                        if sender.send(result).is_err() {
+                            break;
+                        }
+                        if let Some(waker) = &waker {
+                            waker.wake();
+                        }
+                    }
+                }
+                was_active = is_active;
+
+                // Sleep out the period, re-timing it whenever a setting changes
+                loop {
+                    let interval = Duration::from_millis(period.load(Ordering::Relaxed));
+                    let remaining = interval.saturating_sub(started.elapsed());
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    match wakeups.recv_timeout(remaining) {
+                        // The owner is gone
+                        Err(RecvTimeoutError::Disconnected) => return,
+                        // Starting or stopping applies right away
+                        Ok(()) if flag.load(Ordering::Relaxed) != is_active => break,
+                        _ => {}
+                    }
+                }
+            }
+        });
+
+        Self {
+            receiver,
+            active,
+            interval_ms,
+            wake,
+        }
+    }
+
+    /// Start or stop collecting; idle workers only sleep
+    pub fn set_active(&self, active: bool) {
+        if self.active.swap(active, Ordering::Relaxed) != active {
+            let _ = self.wake.send(());
+        }
+    }
+
+    /// Change the collection period, cutting the current sleep short
+    pub fn set_interval(&self, interval: Duration) {
+        let interval_ms = interval.as_millis() as u64;
+        if self.interval_ms.swap(interval_ms, Ordering::Relaxed) != interval_ms {
+            let _ = self.wake.send(());
+        }
+    }
+
+    /// Results delivered since the last call, oldest first
+    pub fn drain(&self) -> Vec<T> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// Most recent result delivered since the last call
+    pub fn latest(&self) -> Option<T> {
+        self.receiver.try_iter().last()
+    }
+}
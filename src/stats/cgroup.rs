@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::types::CgroupUsage;
+use crate::topology::parse_cpu_list;
+
+/// Default cgroup v2 mount point
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Raw counters read from a cgroup's cpu.stat
+#[derive(Debug, Clone, Copy, Default)]
+struct CpuStat {
+    usage_usec: u64,
+    nr_throttled: u64,
+    throttled_usec: u64,
+}
+
+/// cgroup v2 CPU usage collector
+///
+/// Walks the unified hierarchy and computes per-cgroup usage from
+/// `cpu.stat` counter deltas between two refreshes.
+pub struct CgroupCollector {
+    root: PathBuf,
+    previous: HashMap<PathBuf, CpuStat>,
+    last_refresh: Option<Instant>,
+}
+
+impl CgroupCollector {
+    pub fn new() -> Self {
+        Self {
+            root: PathBuf::from(CGROUP_ROOT),
+            previous: HashMap::new(),
+            last_refresh: None,
+        }
+    }
+
+    /// Walk the hierarchy and return usage for every cgroup, busiest first
+    ///
+    /// The first call after construction only primes the counters and
+    /// reports zero usage.
+    pub fn refresh(&mut self) -> Vec<CgroupUsage> {
+        let now = Instant::now();
+        let elapsed_usec = self
+            .last_refresh
+            .map(|t| now.duration_since(t).as_micros() as u64)
+            .unwrap_or(0);
+        self.last_refresh = Some(now);
+
+        let root_cpus = read_cpu_list(&self.root.join("cpuset.cpus.effective"))
+            .or_else(|| read_cpu_list(Path::new("/sys/devices/system/cpu/online")))
+            .unwrap_or_default();
+
+        let mut current = HashMap::new();
+        let mut usages = Vec::new();
+
+        let mut pending = vec![(self.root.clone(), root_cpus)];
+        while let Some((dir, parent_cpus)) = pending.pop() {
+            let cpus = read_cpu_list(&dir.join("cpuset.cpus.effective")).unwrap_or(parent_cpus);
+
+            if dir != self.root {
+                if let Some(stat) = read_cpu_stat(&dir.join("cpu.stat")) {
+                    if let Some(usage) = self.usage_for(&dir, stat, &cpus, elapsed_usec) {
+                        usages.push(usage);
+                    }
+                    current.insert(dir.clone(), stat);
+                }
+            }
+
+            if let Ok(entries) = fs::read_dir(&dir) {
+                for entry in entries.flatten() {
+                    if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                        pending.push((entry.path(), cpus.clone()));
+                    }
+                }
+            }
+        }
+
+        self.previous = current;
+
+        usages.sort_by(|a, b| {
+            b.cpu_count_used
+                .partial_cmp(&a.cpu_count_used)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        usages
+    }
+
+    fn usage_for(
+        &self,
+        dir: &Path,
+        stat: CpuStat,
+        cpus: &[usize],
+        elapsed_usec: u64,
+    ) -> Option<CgroupUsage> {
+        let path = dir.strip_prefix(&self.root).ok()?;
+        let path = format!("/{}", path.display());
+
+        let (cpu_count_used, nr_throttled, throttled_usec) =
+            match (self.previous.get(dir), elapsed_usec) {
+                (Some(prev), elapsed) if elapsed > 0 => (
+                    stat.usage_usec.saturating_sub(prev.usage_usec) as f32 / elapsed as f32,
+                    stat.nr_throttled.saturating_sub(prev.nr_throttled),
+                    stat.throttled_usec.saturating_sub(prev.throttled_usec),
+                ),
+                _ => (0.0, 0, 0),
+            };
+
+        let usage_percent = if cpus.is_empty() {
+            0.0
+        } else {
+            (cpu_count_used / cpus.len() as f32 * 100.0).min(100.0)
+        };
+
+        Some(CgroupUsage {
+            path,
+            cpus: cpus.to_vec(),
+            cpu_count_used,
+            usage_percent,
+            nr_throttled,
+            throttled_usec,
+        })
+    }
+}
+
+impl Default for CgroupCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_cpu_list(path: &Path) -> Option<Vec<usize>> {
+    let content = fs::read_to_string(path).ok()?;
+    let cpus = parse_cpu_list(&content);
+    if cpus.is_empty() {
+        None
+    } else {
+        Some(cpus)
+    }
+}
+
+fn read_cpu_stat(path: &Path) -> Option<CpuStat> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut stat = CpuStat::default();
+
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(key), Some(value)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let Ok(value) = value.parse::<u64>() else {
+            continue;
+        };
+        match key {
+            "usage_usec" => stat.usage_usec = value,
+            "nr_throttled" => stat.nr_throttled = value,
+            "throttled_usec" => stat.throttled_usec = value,
+            _ => {}
+        }
+    }
+
+    Some(stat)
+}
@@ -1,11 +1,19 @@
+use std::collections::HashMap;
 use std::time::Instant;
 use sysinfo::{CpuRefreshKind, RefreshKind, System};
 
+use super::procstat::{read_proc_stat, CpuTimes};
+use super::source::Sampler;
 use super::types::{CoreUsage, SystemStats};
 
 /// CPU statistics collector using sysinfo
+///
+/// Steal and guest time are not exposed by sysinfo, so they are derived
+/// from /proc/stat deltas alongside.
 pub struct StatsCollector {
     system: System,
+    prev_times: HashMap<usize, CpuTimes>,
+    last_refresh: Instant,
 }
 
 impl StatsCollector {
@@ -19,36 +27,50 @@ impl StatsCollector {
         std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
         system.refresh_cpu_usage();
 
-        Self { system }
+        Self {
+            system,
+            prev_times: read_proc_stat(),
+            last_refresh: Instant::now(),
+        }
     }
 
     /// Refresh CPU stats and return current snapshot
     pub fn refresh(&mut self) -> SystemStats {
         self.system.refresh_cpu_usage();
+        let times = read_proc_stat();
 
         let core_usages: Vec<CoreUsage> = self
             .system
             .cpus()
             .iter()
             .enumerate()
-            .map(|(idx, cpu)| CoreUsage {
-                core_id: idx,
-                usage_percent: cpu.cpu_usage(),
-                frequency_mhz: Some(cpu.frequency()),
+            .map(|(idx, cpu)| {
+                // sysinfo lists online CPUs only, so its position drifts from
+                // the kernel's CPU number once one goes offline
+                let cpu_id = cpu
+                    .name()
+                    .strip_prefix("cpu")
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(idx);
+                let (steal_percent, guest_percent) =
+                    match (times.get(&cpu_id), self.prev_times.get(&cpu_id)) {
+                        (Some(now), Some(prev)) => now.steal_guest_since(prev),
+                        _ => (0.0, 0.0),
+                    };
+                CoreUsage {
+                    core_id: cpu_id,
+                    usage_percent: cpu.cpu_usage(),
+                    steal_percent,
+                    guest_percent,
+                    frequency_mhz: Some(cpu.frequency()),
+                }
             })
             .collect();
+        self.prev_times = times;
 
-        let total_usage = if core_usages.is_empty() {
-            0.0
-        } else {
-            core_usages.iter().map(|u| u.usage_percent).sum::<f32>() / core_usages.len() as f32
-        };
-
-        SystemStats {
-            timestamp: Instant::now(),
-            core_usages,
-            total_usage,
-        }
+        let stats = SystemStats::from_core_usages(core_usages, self.last_refresh);
+        self.last_refresh = stats.timestamp;
+        stats
     }
 
     /// Get number of CPUs
@@ -57,6 +79,12 @@ impl StatsCollector {
     }
 }
 
+impl Sampler for StatsCollector {
+    fn sample(&mut self) -> SystemStats {
+        self.refresh()
+    }
+}
+
 impl Default for StatsCollector {
     fn default() -> Self {
         Self::new()
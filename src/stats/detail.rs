@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::procstat::{read_proc_stat, CpuTimes};
+use super::temperature::{read_temperatures, Temperatures};
+use crate::topology::ZenTopology;
+
+/// Processes listed per CPU in the detail panel
+const TOP_PROCESSES: usize = 5;
+
+/// Where a CPU's time went over the last interval, in percent
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuBreakdown {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+    /// Time spent running guests (already part of user and nice)
+    pub guest: f32,
+}
+
+impl CpuBreakdown {
+    fn between(now: &CpuTimes, prev: &CpuTimes) -> Self {
+        let total = now.total().saturating_sub(prev.total());
+        if total == 0 {
+            return Self::default();
+        }
+        let pct = |now: u64, prev: u64| now.saturating_sub(prev) as f32 / total as f32 * 100.0;
+        Self {
+            user: pct(now.user, prev.user),
+            nice: pct(now.nice, prev.nice),
+            system: pct(now.system, prev.system),
+            iowait: pct(now.iowait, prev.iowait),
+            irq: pct(now.irq, prev.irq),
+            softirq: pct(now.softirq, prev.softirq),
+            steal: pct(now.steal, prev.steal),
+            guest: pct(now.guest + now.guest_nice, prev.guest + prev.guest_nice),
+        }
+    }
+}
+
+/// CPU time a process's threads spent on one CPU over the last interval
+#[derive(Debug, Clone)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    /// Share of the CPU's elapsed time
+    pub usage_percent: f32,
+}
+
+/// Per-CPU data for the detail panel that only the local host can provide
+#[derive(Debug, Clone, Default)]
+pub struct CpuDetails {
+    pub breakdowns: HashMap<usize, CpuBreakdown>,
+    /// Busiest processes keyed by CPU, busiest first
+    pub processes: HashMap<usize, Vec<ProcessUsage>>,
+    pub temperatures: Temperatures,
+}
+
+/// Thread counters from `/proc/<pid>/task/<tid>/stat`
+struct ThreadStat {
+    pid: u32,
+    tid: u32,
+    /// utime + stime, in the same clock ticks as /proc/stat
+    ticks: u64,
+    /// CPU the thread last ran on
+    cpu: usize,
+}
+
+/// Collector for the detail panel
+///
+/// A thread's CPU time since the previous refresh is charged to the CPU it
+/// last ran on, which is exact for pinned threads and a good estimate for
+/// threads that stay put for most of an interval.
+pub struct DetailCollector {
+    topology: ZenTopology,
+    prev_times: HashMap<usize, CpuTimes>,
+    prev_threads: HashMap<u32, u64>,
+}
+
+impl DetailCollector {
+    pub fn new(topology: ZenTopology) -> Self {
+        Self {
+            topology,
+            prev_times: HashMap::new(),
+            prev_threads: HashMap::new(),
+        }
+    }
+
+    /// Read /proc and hwmon; the first call only primes the counters
+    pub fn refresh(&mut self) -> CpuDetails {
+        let times = read_proc_stat();
+        let threads = scan_threads();
+
+        let breakdowns = times
+            .iter()
+            .filter_map(|(&cpu_id, now)| {
+                let prev = self.prev_times.get(&cpu_id)?;
+                Some((cpu_id, CpuBreakdown::between(now, prev)))
+            })
+            .collect();
+
+        // Ticks per (CPU, process), from threads seen on both refreshes
+        let mut ticks: HashMap<(usize, u32), u64> = HashMap::new();
+        for thread in &threads {
+            if let Some(&prev) = self.prev_threads.get(&thread.tid) {
+                let delta = thread.ticks.saturating_sub(prev);
+                if delta > 0 {
+                    *ticks.entry((thread.cpu, thread.pid)).or_default() += delta;
+                }
+            }
+        }
+
+        let mut processes: HashMap<usize, Vec<ProcessUsage>> = HashMap::new();
+        for ((cpu_id, pid), delta) in ticks {
+            let elapsed = match (times.get(&cpu_id), self.prev_times.get(&cpu_id)) {
+                (Some(now), Some(prev)) => now.total().saturating_sub(prev.total()),
+                _ => 0,
+            };
+            if elapsed == 0 {
+                continue;
+            }
+            processes.entry(cpu_id).or_default().push(ProcessUsage {
+                pid,
+                name: String::new(),
+                usage_percent: (delta as f32 / elapsed as f32 * 100.0).min(100.0),
+            });
+        }
+        for list in processes.values_mut() {
+            list.sort_by(|a, b| b.usage_percent.total_cmp(&a.usage_percent));
+            list.truncate(TOP_PROCESSES);
+            // Only look up names for the processes that are shown
+            for process in list.iter_mut() {
+                process.name = read_comm(process.pid);
+            }
+        }
+
+        self.prev_times = times;
+        self.prev_threads = threads.iter().map(|t| (t.tid, t.ticks)).collect();
+
+        CpuDetails {
+            breakdowns,
+            processes,
+            temperatures: read_temperatures(&self.topology),
+        }
+    }
+}
+
+/// Counters of every thread on the system
+fn scan_threads() -> Vec<ThreadStat> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .flat_map(|pid| {
+            let task_dir = Path::new("/proc").join(pid.to_string()).join("task");
+            fs::read_dir(task_dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter_map(move |entry| {
+                    let tid: u32 = entry.file_name().to_str()?.parse().ok()?;
+                    let stat = fs::read_to_string(entry.path().join("stat")).ok()?;
+                    let (ticks, cpu) = parse_thread_stat(&stat)?;
+                    Some(ThreadStat {
+                        pid,
+                        tid,
+                        ticks,
+                        cpu,
+                    })
+                })
+        })
+        .collect()
+}
+
+/// Extract utime + stime (fields 14 and 15) and `processor` (field 39)
+fn parse_thread_stat(stat: &str) -> Option<(u64, usize)> {
+    // The command name may contain spaces, so start after its closing paren
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    // Fields after comm start at field 3 (state)
+    let field = |n: usize| fields.get(n - 3).copied();
+    let utime: u64 = field(14)?.parse().ok()?;
+    let stime: u64 = field(15)?.parse().ok()?;
+    let cpu = field(39)?.parse().ok()?;
+    Some((utime + stime, cpu))
+}
+
+fn read_comm(pid: u32) -> String {
+    fs::read_to_string(format!("/proc/{}/comm", pid))
+        .map(|comm| comm.trim().to_string())
+        .unwrap_or_else(|_| format!("pid {}", pid))
+}
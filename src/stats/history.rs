@@ -0,0 +1,142 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use super::types::SystemStats;
+use crate::topology::{ZenTopology, MAX_CPUS};
+
+/// Kind of CPU group tracked in history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+    Ccd,
+    Nps,
+}
+
+/// Usage of every CPU and group at one point in time
+#[derive(Debug, Clone)]
+pub struct HistorySample {
+    pub timestamp: Instant,
+    /// Usage per logical CPU, indexed by CPU ID
+    pub cpus: Vec<f32>,
+    /// Usage per CCD, in `ZenTopology::ccds` order
+    pub ccds: Vec<f32>,
+    /// Usage per NPS node, in `ZenTopology::nps_nodes` order
+    pub nps: Vec<f32>,
+}
+
+/// Bounded ring buffer of usage samples
+#[derive(Debug, Clone)]
+pub struct History {
+    depth: usize,
+    samples: VecDeque<HistorySample>,
+    /// Group IDs matching the sample vectors' order
+    ccd_ids: Vec<usize>,
+    nps_ids: Vec<usize>,
+}
+
+impl History {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth: depth.max(1),
+            samples: VecDeque::with_capacity(depth.max(1)),
+            ccd_ids: Vec::new(),
+            nps_ids: Vec::new(),
+        }
+    }
+
+    /// Record a snapshot, dropping the oldest sample when full
+    pub fn push(&mut self, stats: &SystemStats, topology: &ZenTopology) {
+        // Indexed by CPU ID, which need not match positions once CPUs are
+        // offline; IDs from a misbehaving agent cannot grow it without bound
+        let usages = stats.core_usages.iter().filter(|u| u.core_id < MAX_CPUS);
+        let mut cpus = vec![0.0; usages.clone().map(|u| u.core_id + 1).max().unwrap_or(0)];
+        for usage in usages {
+            cpus[usage.core_id] = usage.usage_percent;
+        }
+        let average = |members: &[usize]| {
+            let values: Vec<f32> = members
+                .iter()
+                .filter_map(|&id| stats.usage(id).map(|u| u.usage_percent))
+                .collect();
+            if values.is_empty() {
+                0.0
+            } else {
+                values.iter().sum::<f32>() / values.len() as f32
+            }
+        };
+
+        let sample = HistorySample {
+            timestamp: stats.timestamp,
+            ccds: topology.ccds.iter().map(|c| average(&c.cores)).collect(),
+            nps: topology
+                .nps_nodes
+                .iter()
+                .map(|n| average(&n.cores))
+                .collect(),
+            cpus,
+        };
+
+        self.ccd_ids = topology.ccds.iter().map(|c| c.id).collect();
+        self.nps_ids = topology.nps_nodes.iter().map(|n| n.id).collect();
+
+        if self.samples.len() == self.depth {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Maximum number of samples kept
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Drop all samples
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Samples from oldest to newest
+    pub fn samples(&self) -> impl Iterator<Item = &HistorySample> {
+        self.samples.iter()
+    }
+
+    /// Usage series of a single CPU, oldest first
+    pub fn cpu_series(&self, cpu_id: usize) -> Vec<f32> {
+        self.samples
+            .iter()
+            .map(|s| s.cpus.get(cpu_id).copied().unwrap_or(0.0))
+            .collect()
+    }
+
+    /// Usage series of a CCD or NPS node by group ID, oldest first
+    pub fn group_series(&self, kind: GroupKind, group_id: usize) -> Vec<f32> {
+        let ids = match kind {
+            GroupKind::Ccd => &self.ccd_ids,
+            GroupKind::Nps => &self.nps_ids,
+        };
+        let Some(index) = ids.iter().position(|&id| id == group_id) else {
+            return Vec::new();
+        };
+
+        self.samples
+            .iter()
+            .map(|s| {
+                let values = match kind {
+                    GroupKind::Ccd => &s.ccds,
+                    GroupKind::Nps => &s.nps,
+                };
+                values.get(index).copied().unwrap_or(0.0)
+            })
+            .collect()
+    }
+
+    /// Seconds between each sample and the newest one (negative, oldest first)
+    pub fn time_offsets(&self) -> Vec<f64> {
+        let Some(latest) = self.samples.back().map(|s| s.timestamp) else {
+            return Vec::new();
+        };
+        self.samples
+            .iter()
+            .map(|s| -(latest.duration_since(s.timestamp).as_secs_f64()))
+            .collect()
+    }
+}
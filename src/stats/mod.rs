@@ -1,5 +1,20 @@
+pub mod background;
+pub mod cgroup;
 pub mod collector;
+pub mod detail;
+pub mod history;
+pub mod procstat;
+pub mod source;
+pub mod synthetic;
+pub mod temperature;
 pub mod types;
+pub mod vcpu;
 
-pub use collector::StatsCollector;
+pub use background::Background;
+pub use cgroup::CgroupCollector;
+pub use detail::{CpuBreakdown, CpuDetails, DetailCollector, ProcessUsage};
+pub use history::{GroupKind, History};
+pub use source::{sampler, LiveSource, Playback, StatsSource};
+pub use temperature::{read_temperatures, Temperatures};
 pub use types::*;
+pub use vcpu::VcpuScanner;
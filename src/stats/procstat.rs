@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::source::Sampler;
+use super::types::{CoreUsage, SystemStats};
+
+/// Delay between the priming read and the first sample
+const PRIME_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Cumulative per-CPU jiffies from a `cpuN` line of /proc/stat
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimes {
+    /// Total elapsed jiffies (guest time is already accounted in user/nice)
+    pub fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+
+    /// Busy percentage relative to an earlier sample (idle and iowait excluded)
+    pub fn usage_since(&self, prev: &CpuTimes) -> f32 {
+        let total = self.total().saturating_sub(prev.total());
+        if total == 0 {
+            return 0.0;
+        }
+        let idle = (self.idle + self.iowait).saturating_sub(prev.idle + prev.iowait);
+        total.saturating_sub(idle) as f32 / total as f32 * 100.0
+    }
+
+    /// Steal and guest percentages relative to an earlier sample
+    pub fn steal_guest_since(&self, prev: &CpuTimes) -> (f32, f32) {
+        let total = self.total().saturating_sub(prev.total());
+        if total == 0 {
+            return (0.0, 0.0);
+        }
+        let steal = self.steal.saturating_sub(prev.steal);
+        let guest = (self.guest + self.guest_nice).saturating_sub(prev.guest + prev.guest_nice);
+        (
+            steal as f32 / total as f32 * 100.0,
+            guest as f32 / total as f32 * 100.0,
+        )
+    }
+}
+
+/// CPU statistics read straight from /proc/stat and cpufreq
+///
+/// Lighter than sysinfo, and usage is computed from the same counters as
+/// steal and guest time, so the three always add up.
+pub struct ProcStatCollector {
+    prev_times: HashMap<usize, CpuTimes>,
+    last_sample: Instant,
+}
+
+impl ProcStatCollector {
+    pub fn new() -> Self {
+        let prev_times = read_proc_stat();
+        let last_sample = Instant::now();
+        // The first sample needs an interval to compute deltas over
+        thread::sleep(PRIME_INTERVAL);
+        Self {
+            prev_times,
+            last_sample,
+        }
+    }
+}
+
+impl Sampler for ProcStatCollector {
+    fn sample(&mut self) -> SystemStats {
+        let times = read_proc_stat();
+
+        let mut cpu_ids: Vec<usize> = times.keys().copied().collect();
+        cpu_ids.sort();
+        let core_usages = cpu_ids
+            .into_iter()
+            .map(|cpu_id| {
+                let now = &times[&cpu_id];
+                let (usage_percent, (steal_percent, guest_percent)) =
+                    match self.prev_times.get(&cpu_id) {
+                        Some(prev) => (now.usage_since(prev), now.steal_guest_since(prev)),
+                        None => (0.0, (0.0, 0.0)),
+                    };
+                CoreUsage {
+                    core_id: cpu_id,
+                    usage_percent,
+                    steal_percent,
+                    guest_percent,
+                    frequency_mhz: read_cur_freq_mhz(cpu_id),
+                }
+            })
+            .collect();
+        self.prev_times = times;
+
+        let stats = SystemStats::from_core_usages(core_usages, self.last_sample);
+        self.last_sample = stats.timestamp;
+        stats
+    }
+}
+
+/// Current frequency from cpufreq, if the CPU exposes it
+fn read_cur_freq_mhz(cpu_id: usize) -> Option<u64> {
+    let path = format!(
+        "/sys/devices/system/cpu/cpu{}/cpufreq/scaling_cur_freq",
+        cpu_id
+    );
+    let khz: u64 = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    Some(khz / 1000)
+}
+
+/// Read per-CPU times from /proc/stat, keyed by logical CPU ID
+pub fn read_proc_stat() -> HashMap<usize, CpuTimes> {
+    fs::read_to_string("/proc/stat")
+        .map(|content| parse_proc_stat(&content))
+        .unwrap_or_default()
+}
+
+fn parse_proc_stat(content: &str) -> HashMap<usize, CpuTimes> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let cpu_id = fields.next()?.strip_prefix("cpu")?.parse::<usize>().ok()?;
+            let values: Vec<u64> = fields.filter_map(|f| f.parse().ok()).collect();
+            let get = |idx: usize| values.get(idx).copied().unwrap_or(0);
+
+            Some((
+                cpu_id,
+                CpuTimes {
+                    user: get(0),
+                    nice: get(1),
+                    system: get(2),
+                    idle: get(3),
+                    iowait: get(4),
+                    irq: get(5),
+                    softirq: get(6),
+                    steal: get(7),
+                    guest: get(8),
+                    guest_nice: get(9),
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed from a 4-CPU guest with CPU 2 offline
+    const PROC_STAT: &str = "\
+cpu  52311 120 20433 2093852 1711 0 998 3050 8123 0
+cpu0 13187 31 5172 523019 418 0 611 762 2031 0
+cpu1 13050 28 5101 523647 433 0 150 771 2030 0
+cpu3 12966 33 5006 523741 430 0 101 754 2028 0
+intr 8741234 21 9 0 0 0 0 0 0 0 0 0 0
+ctxt 15290335
+btime 1714550000
+processes 40711
+procs_running 2
+procs_blocked 0
+softirq 3489123 0 1091234 12 201234 51234 0 1234 1102345 0 1041796
+";
+
+    #[test]
+    fn parses_per_cpu_lines_by_kernel_number() {
+        let times = parse_proc_stat(PROC_STAT);
+
+        let mut ids: Vec<usize> = times.keys().copied().collect();
+        ids.sort();
+        assert_eq!(ids, vec![0, 1, 3]);
+
+        let cpu3 = times[&3];
+        assert_eq!(cpu3.user, 12966);
+        assert_eq!(cpu3.nice, 33);
+        assert_eq!(cpu3.system, 5006);
+        assert_eq!(cpu3.idle, 523741);
+        assert_eq!(cpu3.iowait, 430);
+        assert_eq!(cpu3.softirq, 101);
+        assert_eq!(cpu3.steal, 754);
+        assert_eq!(cpu3.guest, 2028);
+        assert_eq!(cpu3.guest_nice, 0);
+    }
+
+    #[test]
+    fn missing_trailing_fields_read_as_zero() {
+        // Kernels before 2.6.33 stop after steal
+        let times = parse_proc_stat("cpu0 10 0 5 100 2 0 1 3\n");
+        let cpu0 = times[&0];
+        assert_eq!(cpu0.steal, 3);
+        assert_eq!(cpu0.guest, 0);
+        assert_eq!(cpu0.guest_nice, 0);
+    }
+
+    #[test]
+    fn usage_excludes_idle_and_iowait() {
+        let prev = CpuTimes {
+            user: 100,
+            idle: 800,
+            iowait: 100,
+            ..CpuTimes::default()
+        };
+        let now = CpuTimes {
+            user: 150,
+            system: 20,
+            idle: 900,
+            iowait: 110,
+            steal: 20,
+            guest: 10,
+            ..CpuTimes::default()
+        };
+        // 200 jiffies elapsed, 110 of them idle or waiting on I/O
+        assert!((now.usage_since(&prev) - 45.0).abs() < 1e-4);
+        let (steal, guest) = now.steal_guest_since(&prev);
+        assert!((steal - 10.0).abs() < 1e-4);
+        assert!((guest - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn no_elapsed_time_means_no_usage() {
+        let times = CpuTimes {
+            user: 5,
+            idle: 5,
+            ..CpuTimes::default()
+        };
+        assert_eq!(times.usage_since(&times), 0.0);
+        assert_eq!(times.steal_guest_since(&times), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use super::background::Background;
+use super::collector::StatsCollector;
+use super::procstat::ProcStatCollector;
+use super::synthetic::SyntheticCollector;
+use super::types::SystemStats;
+use crate::config::SourceKind;
+use crate::event::Waker;
+use crate::topology::ZenTopology;
+
+/// Where snapshots shown by the UI come from
+///
+/// `poll` is called from the UI thread on every tick and must not block;
+/// sources that do real work sample on their own thread and wake the UI
+/// when a snapshot is ready.
+pub trait StatsSource {
+    /// Snapshots that became available since the last call, oldest first
+    fn poll(&mut self) -> Vec<SystemStats>;
+
+    /// Status text for the header, e.g. a replay position or remote host
+    fn status(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether snapshots describe this host (enables cgroup, IRQ and vCPU collectors)
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    /// Whether the source can still deliver snapshots
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    /// Whether the source wakes the event loop when snapshots arrive;
+    /// other sources are polled every refresh interval
+    fn wakes(&self) -> bool {
+        false
+    }
+
+    /// Change the sampling period, for sources that sample themselves
+    fn set_interval(&mut self, _interval: Duration) {}
+
+    /// Interactive playback controls, for sources that play back recorded data
+    fn playback(&mut self) -> Option<&mut dyn Playback> {
+        None
+    }
+}
+
+/// Pause, step, seek and speed controls of a recorded source
+pub trait Playback {
+    fn toggle_pause(&mut self);
+
+    /// Move one sample forward and pause
+    fn step(&mut self) -> Option<SystemStats>;
+
+    fn seek_backward(&mut self);
+
+    fn seek_forward(&mut self);
+
+    fn faster(&mut self);
+
+    fn slower(&mut self);
+
+    /// Samples up to the current position, at most `count`, oldest first
+    fn window(&self, count: usize) -> Vec<SystemStats>;
+}
+
+/// Synchronous snapshot collector, driven by a sampling thread
+pub trait Sampler: Send {
+    fn sample(&mut self) -> SystemStats;
+}
+
+/// Build the collector for a live source
+///
+/// May block briefly (collectors prime their counters), so TUI sessions
+/// call it on the sampling thread.
+pub fn sampler(kind: SourceKind, topology: &ZenTopology) -> Box<dyn Sampler> {
+    match kind {
+        SourceKind::Sysinfo => Box::new(StatsCollector::new()),
+        SourceKind::Procstat => Box::new(ProcStatCollector::new()),
+        SourceKind::Synthetic => Box::new(SyntheticCollector::new(topology)),
+    }
+}
+
+/// Snapshots of this host, sampled on a dedicated thread
+pub struct LiveSource {
+    worker: Background<SystemStats>,
+}
+
+impl LiveSource {
+    pub fn spawn(
+        kind: SourceKind,
+        topology: &ZenTopology,
+        interval: Duration,
+        waker: Waker,
+    ) -> Self {
+        let topology = topology.clone();
+        let worker = Background::spawn_waking(
+            move || {
+                let mut sampler = sampler(kind, &topology);
+                move || sampler.sample()
+            },
+            interval,
+            true,
+            waker,
+        );
+        Self { worker }
+    }
+}
+
+impl StatsSource for LiveSource {
+    fn poll(&mut self) -> Vec<SystemStats> {
+        self.worker.drain()
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn wakes(&self) -> bool {
+        true
+    }
+
+    fn set_interval(&mut self, interval: Duration) {
+        self.worker.set_interval(interval);
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Instant;
+
+use super::source::Sampler;
+use super::types::{CoreUsage, SystemStats};
+use crate::topology::ZenTopology;
+
+/// Base clock reported for idle CPUs
+const BASE_MHZ: f32 = 2000.0;
+
+/// Extra clock reported for fully busy CPUs
+const BOOST_MHZ: f32 = 1500.0;
+
+/// Generated load for demos and for exercising views without a busy host
+///
+/// Each CCD follows a slow wave with its own phase and each CPU adds a
+/// faster ripple, so group aggregates, heatmaps and history all move.
+pub struct SyntheticCollector {
+    /// (CPU ID, CCD ID) for every CPU in the topology
+    cpus: Vec<(usize, usize)>,
+    start: Instant,
+    last_sample: Instant,
+}
+
+impl SyntheticCollector {
+    pub fn new(topology: &ZenTopology) -> Self {
+        // Snapshots are indexed by CPU ID
+        let mut cpus: Vec<(usize, usize)> =
+            topology.cores.iter().map(|c| (c.id, c.ccd_id)).collect();
+        cpus.sort();
+        Self {
+            cpus,
+            start: Instant::now(),
+            last_sample: Instant::now(),
+        }
+    }
+}
+
+impl Sampler for SyntheticCollector {
+    fn sample(&mut self) -> SystemStats {
+        let t = self.start.elapsed().as_secs_f32();
+
+        let core_usages = self
+            .cpus
+            .iter()
+            .map(|&(cpu_id, ccd_id)| {
+                let wave = (t * 0.3 + ccd_id as f32 * 0.9).sin();
+                let ripple = (t * 1.7 + cpu_id as f32 * 2.3).sin();
+                let usage_percent = (50.0 + wave * 40.0 + ripple * 10.0).clamp(0.0, 100.0);
+                CoreUsage {
+                    core_id: cpu_id,
+                    usage_percent,
+                    steal_percent: 0.0,
+                    guest_percent: 0.0,
+                    frequency_mhz: Some((BASE_MHZ + usage_percent / 100.0 * BOOST_MHZ) as u64),
+                }
+            })
+            .collect();
+
+        let stats = SystemStats::from_core_usages(core_usages, self.last_sample);
+        self.last_sample = stats.timestamp;
+        stats
+    }
+}
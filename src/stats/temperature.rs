@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::topology::ZenTopology;
+
+/// Temperatures reported by the k10temp driver, in degrees Celsius
+///
+/// k10temp exposes one hwmon device per socket with a control temperature
+/// (`Tctl`) and one sensor per populated CCD (`Tccd1`, `Tccd2`, ...).
+#[derive(Debug, Clone, Default)]
+pub struct Temperatures {
+    /// Tctl keyed by package ID
+    pub packages: HashMap<usize, f32>,
+    /// Tccd keyed by CCD ID
+    pub ccds: HashMap<usize, f32>,
+}
+
+impl Temperatures {
+    /// Temperature of the CCD a CPU belongs to (Zen has no per-core sensor)
+    pub fn cpu(&self, topology: &ZenTopology, cpu_id: usize) -> Option<f32> {
+        let core = topology.core(cpu_id)?;
+        self.ccds.get(&core.ccd_id).copied()
+    }
+}
+
+/// Read k10temp sensors from /sys/class/hwmon
+///
+/// Sensors are matched to sockets by hwmon device order and to CCDs by
+/// their position within the socket, which is how k10temp numbers them.
+pub fn read_temperatures(topology: &ZenTopology) -> Temperatures {
+    let mut temps = Temperatures::default();
+
+    let mut devices: Vec<_> = match fs::read_dir("/sys/class/hwmon") {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| read_trimmed(&p.join("name")).as_deref() == Some("k10temp"))
+            .collect(),
+        Err(_) => return temps,
+    };
+    // hwmon numbering is not stable; the underlying PCI device order is
+    devices.sort_by_key(|p| fs::canonicalize(p.join("device")).ok());
+
+    for (package_id, device) in devices.iter().enumerate() {
+        let mut socket_ccds: Vec<usize> = topology
+            .ccds
+            .iter()
+            .filter(|c| c.package_id == package_id)
+            .map(|c| c.id)
+            .collect();
+        socket_ccds.sort();
+
+        for (label, value) in read_sensors(device) {
+            if label == "Tctl" {
+                temps.packages.insert(package_id, value);
+            } else if let Some(n) = label.strip_prefix("Tccd") {
+                let ccd = n
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| socket_ccds.get(n.checked_sub(1)?));
+                if let Some(&ccd_id) = ccd {
+                    temps.ccds.insert(ccd_id, value);
+                }
+            }
+        }
+    }
+
+    temps
+}
+
+/// (label, degrees Celsius) for every tempN_input with a label
+fn read_sensors(device: &Path) -> Vec<(String, f32)> {
+    let Ok(entries) = fs::read_dir(device) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let prefix = name.strip_suffix("_input")?;
+            if !prefix.starts_with("temp") {
+                return None;
+            }
+            let label = read_trimmed(&device.join(format!("{}_label", prefix)))?;
+            let millidegrees: f32 = read_trimmed(&entry.path())?.parse().ok()?;
+            Some((label, millidegrees / 1000.0))
+        })
+        .collect()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
@@ -1,10 +1,16 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
 
 /// CPU usage for a single core
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoreUsage {
     pub core_id: usize,
     pub usage_percent: f32,
+    /// Time stolen by the hypervisor (percent of interval)
+    pub steal_percent: f32,
+    /// Time spent running guests (percent of interval, included in usage)
+    pub guest_percent: f32,
     pub frequency_mhz: Option<u64>,
 }
 
@@ -53,19 +59,112 @@ impl GroupUsage {
 }
 
 /// Complete system stats snapshot
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemStats {
+    /// Monotonic sample time (not serialized; recordings store elapsed time)
+    #[serde(skip, default = "Instant::now")]
     pub timestamp: Instant,
+    /// Time covered by this sample (since the previous one); zero if unknown
+    #[serde(skip)]
+    pub interval: Duration,
     pub core_usages: Vec<CoreUsage>,
     pub total_usage: f32,
+    pub total_steal: f32,
+    pub total_guest: f32,
+}
+
+impl SystemStats {
+    /// Snapshot taken now, covering the time since `previous`, with totals
+    /// averaged over all CPUs
+    pub fn from_core_usages(core_usages: Vec<CoreUsage>, previous: Instant) -> Self {
+        let average = |f: fn(&CoreUsage) -> f32| {
+            if core_usages.is_empty() {
+                0.0
+            } else {
+                core_usages.iter().map(f).sum::<f32>() / core_usages.len() as f32
+            }
+        };
+        let total_usage = average(|u| u.usage_percent);
+        let total_steal = average(|u| u.steal_percent);
+        let total_guest = average(|u| u.guest_percent);
+
+        let timestamp = Instant::now();
+        Self {
+            timestamp,
+            interval: timestamp.duration_since(previous),
+            core_usages,
+            total_usage,
+            total_steal,
+            total_guest,
+        }
+    }
+
+    /// Usage of a CPU by ID; CPU IDs need not match positions in
+    /// `core_usages` once CPUs are offline
+    pub fn usage(&self, cpu_id: usize) -> Option<&CoreUsage> {
+        self.core_usages.iter().find(|u| u.core_id == cpu_id)
+    }
 }
 
 impl Default for SystemStats {
     fn default() -> Self {
         Self {
             timestamp: Instant::now(),
+            interval: Duration::ZERO,
             core_usages: Vec::new(),
             total_usage: 0.0,
+            total_steal: 0.0,
+            total_guest: 0.0,
         }
     }
 }
+
+/// CPU consumption of a single cgroup over the last refresh interval
+#[derive(Debug, Clone)]
+pub struct CgroupUsage {
+    /// Path relative to the cgroup root (e.g. "/system.slice/sshd.service")
+    pub path: String,
+    /// Effective cpuset of the cgroup
+    pub cpus: Vec<usize>,
+    /// CPU time consumed, in CPU-equivalents (1.0 = one fully busy CPU)
+    pub cpu_count_used: f32,
+    /// Usage relative to the cgroup's cpuset capacity
+    pub usage_percent: f32,
+    /// Number of throttled periods during the interval
+    pub nr_throttled: u64,
+    /// Time spent throttled during the interval
+    pub throttled_usec: u64,
+}
+
+impl CgroupUsage {
+    pub fn is_throttled(&self) -> bool {
+        self.nr_throttled > 0 || self.throttled_usec > 0
+    }
+}
+
+/// Placement of a single guest vCPU thread
+#[derive(Debug, Clone)]
+pub struct VcpuPlacement {
+    /// vCPU index inside the guest
+    pub vcpu: usize,
+    /// Host CPU the thread last ran on
+    pub cpu: usize,
+}
+
+/// vCPU placement of a QEMU/KVM guest
+#[derive(Debug, Clone)]
+pub struct GuestPlacement {
+    pub pid: u32,
+    pub name: String,
+    pub vcpus: Vec<VcpuPlacement>,
+}
+
+impl GuestPlacement {
+    /// Host CPUs currently running this guest's vCPUs
+    pub fn host_cpus(&self) -> Vec<usize> {
+        let mut cpus: Vec<usize> = self.vcpus.iter().map(|v| v.cpu).collect();
+        cpus.sort();
+        cpus.dedup();
+        cpus
+    }
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::Path;
+
+use super::types::{GuestPlacement, VcpuPlacement};
+
+/// Scanner for QEMU/KVM vCPU threads
+///
+/// QEMU names its vCPU threads "CPU <n>/KVM"; the host CPU each thread last
+/// ran on is the `processor` field of `/proc/<pid>/task/<tid>/stat`.
+pub struct VcpuScanner;
+
+impl VcpuScanner {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Scan /proc for QEMU processes and return their vCPU placement
+    pub fn scan(&self) -> Vec<GuestPlacement> {
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+
+        let mut guests: Vec<GuestPlacement> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(scan_process)
+            .collect();
+
+        guests.sort_by(|a, b| a.name.cmp(&b.name).then(a.pid.cmp(&b.pid)));
+        guests
+    }
+}
+
+impl Default for VcpuScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn scan_process(pid: u32) -> Option<GuestPlacement> {
+    let proc_dir = Path::new("/proc").join(pid.to_string());
+
+    let comm = fs::read_to_string(proc_dir.join("comm")).ok()?;
+    if !comm.trim().starts_with("qemu") {
+        return None;
+    }
+
+    let mut vcpus: Vec<VcpuPlacement> = fs::read_dir(proc_dir.join("task"))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let comm = fs::read_to_string(entry.path().join("comm")).ok()?;
+            let vcpu = parse_vcpu_comm(comm.trim())?;
+            let stat = fs::read_to_string(entry.path().join("stat")).ok()?;
+            let cpu = parse_last_cpu(&stat)?;
+            Some(VcpuPlacement { vcpu, cpu })
+        })
+        .collect();
+
+    if vcpus.is_empty() {
+        return None;
+    }
+    vcpus.sort_by_key(|v| v.vcpu);
+
+    let name = fs::read(proc_dir.join("cmdline"))
+        .ok()
+        .and_then(|cmdline| parse_guest_name(&cmdline))
+        .unwrap_or_else(|| format!("pid {}", pid));
+
+    Some(GuestPlacement { pid, name, vcpus })
+}
+
+/// Parse a vCPU thread name like "CPU 3/KVM" into its vCPU index
+fn parse_vcpu_comm(comm: &str) -> Option<usize> {
+    comm.strip_prefix("CPU ")?
+        .strip_suffix("/KVM")?
+        .parse()
+        .ok()
+}
+
+/// Extract the `processor` field (39th) from a /proc stat line
+fn parse_last_cpu(stat: &str) -> Option<usize> {
+    // The command name may contain spaces, so start after its closing paren
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    // Fields after comm start at field 3 (state)
+    after_comm.split_whitespace().nth(39 - 3)?.parse().ok()
+}
+
+/// Extract the guest name from QEMU's `-name guest=<name>,...` or `-name <name>`
+fn parse_guest_name(cmdline: &[u8]) -> Option<String> {
+    let args: Vec<String> = cmdline
+        .split(|&b| b == 0)
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+
+    let value = args
+        .iter()
+        .position(|arg| arg == "-name")
+        .and_then(|idx| args.get(idx + 1))?;
+
+    let name = value
+        .split(',')
+        .find_map(|opt| opt.strip_prefix("guest="))
+        .or_else(|| value.split(',').next().filter(|v| !v.contains('=')))?;
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
@@ -0,0 +1,101 @@
+/// Upper bound on CPU IDs (the kernel's largest NR_CPUS); IDs at or above it
+/// are dropped so a bogus range cannot expand into a huge list
+pub const MAX_CPUS: usize = 8192;
+
+/// Parse a kernel cpulist string (e.g. "0-3,8,10-11") into sorted CPU IDs
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+
+    for part in list
+        .trim()
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                    cpus.extend(start..=end.min(MAX_CPUS - 1));
+                }
+            }
+            None => {
+                if let Ok(cpu) = part.parse::<usize>() {
+                    if cpu < MAX_CPUS {
+                        cpus.push(cpu);
+                    }
+                }
+            }
+        }
+    }
+
+    cpus.sort();
+    cpus.dedup();
+    cpus
+}
+
+/// Format CPU IDs as a compact kernel cpulist string (e.g. "0-3,8,10-11")
+pub fn format_cpu_list(cpus: &[usize]) -> String {
+    let mut sorted = cpus.to_vec();
+    sorted.sort();
+    sorted.dedup();
+
+    let mut parts = Vec::new();
+    let mut iter = sorted.into_iter().peekable();
+
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap_or(end);
+        }
+        if start == end {
+            parts.push(start.to_string());
+        } else {
+            parts.push(format!("{}-{}", start, end));
+        }
+    }
+
+    parts.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranges_and_single_cpus() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list(" 5 , 1-2 "), vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn sorts_and_dedups_overlapping_parts() {
+        assert_eq!(parse_cpu_list("4-6,0,5,0-1"), vec![0, 1, 4, 5, 6]);
+    }
+
+    #[test]
+    fn skips_malformed_parts() {
+        assert_eq!(parse_cpu_list("0,x,2-y,3"), vec![0, 3]);
+        assert!(parse_cpu_list("").is_empty());
+        assert!(parse_cpu_list("\n").is_empty());
+    }
+
+    #[test]
+    fn caps_ids_at_max_cpus() {
+        let cpus = parse_cpu_list("8190-4000000000");
+        assert_eq!(cpus, vec![8190, 8191]);
+        assert!(parse_cpu_list(&MAX_CPUS.to_string()).is_empty());
+    }
+
+    #[test]
+    fn formats_runs_compactly() {
+        assert_eq!(format_cpu_list(&[11, 0, 1, 2, 3, 8, 10, 3]), "0-3,8,10-11");
+        assert_eq!(format_cpu_list(&[7]), "7");
+        assert_eq!(format_cpu_list(&[]), "");
+    }
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        let cpus = vec![0, 2, 3, 4, 16, 17, 31];
+        assert_eq!(parse_cpu_list(&format_cpu_list(&cpus)), cpus);
+    }
+}
@@ -88,10 +88,12 @@ pub fn detect_topology() -> Result<ZenTopology> {
                 .find(|c| c.id == *core_ids.first().unwrap_or(&0))
                 .map(|c| c.package_id)
                 .unwrap_or(0);
+            let l3_cache_kb = core_ids.first().and_then(|&cpu_id| read_l3_size_kb(cpu_id));
             Ccd {
                 id,
                 package_id,
                 cores: core_ids,
+                l3_cache_kb,
             }
         })
         .collect();
@@ -109,7 +111,7 @@ pub fn detect_topology() -> Result<ZenTopology> {
             NumaNode {
                 id,
                 cores: core_ids,
-                memory_mb: None,
+                memory_mb: read_node_memory_mb(id),
             }
         })
         .collect();
@@ -166,6 +168,35 @@ fn find_numa_node(
     None
 }
 
+/// Read a CPU's L3 cache size from sysfs (e.g. "32768K")
+fn read_l3_size_kb(cpu_id: usize) -> Option<u64> {
+    let cache_dir = format!("/sys/devices/system/cpu/cpu{}/cache", cpu_id);
+    for entry in std::fs::read_dir(cache_dir).ok()?.flatten() {
+        let path = entry.path();
+        let level = std::fs::read_to_string(path.join("level")).ok();
+        if level.as_deref().map(str::trim) != Some("3") {
+            continue;
+        }
+        let size = std::fs::read_to_string(path.join("size")).ok()?;
+        let size = size.trim();
+        return match size.strip_suffix('K') {
+            Some(kb) => kb.parse().ok(),
+            None => size.strip_suffix('M')?.parse::<u64>().ok().map(|mb| mb * 1024),
+        };
+    }
+    None
+}
+
+/// Read a NUMA node's total memory from sysfs
+fn read_node_memory_mb(node_id: usize) -> Option<u64> {
+    let path = format!("/sys/devices/system/node/node{}/meminfo", node_id);
+    let meminfo = std::fs::read_to_string(path).ok()?;
+    // "Node 0 MemTotal:       263842708 kB"
+    let line = meminfo.lines().find(|l| l.contains("MemTotal:"))?;
+    let kb: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
 fn detect_nps_mode(numa_count: usize, package_count: usize) -> NpsMode {
     if package_count == 0 {
         return NpsMode::Unknown;
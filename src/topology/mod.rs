@@ -1,6 +1,9 @@
+pub mod cpulist;
 pub mod detector;
+pub mod report;
 pub mod types;
 pub mod zen;
 
+pub use cpulist::{format_cpu_list, parse_cpu_list, MAX_CPUS};
 pub use detector::detect_topology;
 pub use types::*;
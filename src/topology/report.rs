@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+
+use super::cpulist::format_cpu_list;
+use super::types::ZenTopology;
+
+/// Print the detected topology as a tree, or as JSON with `json`
+pub fn run(topology: &ZenTopology, json: bool) -> Result<()> {
+    if json {
+        let out = serde_json::to_string_pretty(topology).context("Failed to serialize topology")?;
+        println!("{}", out);
+    } else {
+        print!("{}", format_tree(topology));
+    }
+    Ok(())
+}
+
+/// Human-readable tree: sockets -> NUMA nodes -> CCDs -> SMT pairs
+pub fn format_tree(topology: &ZenTopology) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("{}\n", topology.cpu_model));
+    out.push_str(&format!("  Generation: {}\n", topology.generation));
+    out.push_str(&format!("  NPS mode:   {}\n", topology.nps_mode));
+    out.push_str(&format!("  Sockets:    {}\n", topology.packages));
+    out.push_str(&format!(
+        "  CPUs:       {} cores / {} threads, SMT {}\n",
+        topology.total_cores,
+        topology.total_threads,
+        if topology.smt_enabled { "on" } else { "off" }
+    ));
+    out.push_str(&format!(
+        "  CCDs:       {}, NUMA nodes: {}\n",
+        topology.ccds.len(),
+        topology.numa_nodes.len()
+    ));
+
+    let package_of = |cpus: &[usize]| {
+        cpus.first()
+            .and_then(|&cpu_id| topology.core(cpu_id))
+            .map(|c| c.package_id)
+    };
+    let numa_of = |cpus: &[usize]| {
+        cpus.first()
+            .and_then(|&cpu_id| topology.core(cpu_id))
+            .map(|c| c.numa_node)
+    };
+
+    for socket in 0..topology.packages.max(1) {
+        let socket_cpus: Vec<usize> = topology
+            .cores
+            .iter()
+            .filter(|c| c.package_id == socket)
+            .map(|c| c.id)
+            .collect();
+        out.push_str(&format!(
+            "\nSocket {}  CPUs {}\n",
+            socket,
+            format_cpu_list(&socket_cpus)
+        ));
+
+        let nodes: Vec<_> = topology
+            .numa_nodes
+            .iter()
+            .filter(|n| package_of(&n.cores) == Some(socket))
+            .collect();
+
+        for (node_idx, node) in nodes.iter().enumerate() {
+            let last_node = node_idx + 1 == nodes.len();
+            let memory = node
+                .memory_mb
+                .map(|mb| format!("  memory {}", format_size_mb(mb)))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "{} NUMA {}  CPUs {}{}\n",
+                if last_node { "└─" } else { "├─" },
+                node.id,
+                format_cpu_list(&node.cores),
+                memory
+            ));
+
+            let node_prefix = if last_node { "   " } else { "│  " };
+            let ccds: Vec<_> = topology
+                .ccds
+                .iter()
+                .filter(|c| numa_of(&c.cores) == Some(node.id))
+                .collect();
+
+            for (ccd_idx, ccd) in ccds.iter().enumerate() {
+                let last_ccd = ccd_idx + 1 == ccds.len();
+                let l3 = ccd
+                    .l3_cache_kb
+                    .map(|kb| format!("  L3 {}", format_size_mb(kb / 1024)))
+                    .unwrap_or_default();
+                out.push_str(&format!(
+                    "{}{} CCD {}  CPUs {}{}\n",
+                    node_prefix,
+                    if last_ccd { "└─" } else { "├─" },
+                    ccd.id,
+                    format_cpu_list(&ccd.cores),
+                    l3
+                ));
+
+                let pairs = smt_pairs(topology, &ccd.cores);
+                if !pairs.is_empty() {
+                    out.push_str(&format!(
+                        "{}{}   SMT {}\n",
+                        node_prefix,
+                        if last_ccd { " " } else { "│" },
+                        pairs.join(" ")
+                    ));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// "primary/sibling" pairs for the given CPUs, primary thread first
+fn smt_pairs(topology: &ZenTopology, cpus: &[usize]) -> Vec<String> {
+    cpus.iter()
+        .filter_map(|&cpu_id| topology.core(cpu_id))
+        .filter_map(|c| {
+            c.smt_sibling
+                .filter(|&s| c.id < s)
+                .map(|s| format!("{}/{}", c.id, s))
+        })
+        .collect()
+}
+
+fn format_size_mb(mb: u64) -> String {
+    if mb >= 1024 {
+        format!("{:.1} GiB", mb as f64 / 1024.0)
+    } else {
+        format!("{} MiB", mb)
+    }
+}
@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// Represents a single CPU processing unit (logical CPU)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CpuCore {
     /// Logical CPU ID (matches sysinfo/OS index)
     pub id: usize,
@@ -32,16 +34,18 @@ impl Default for CpuCore {
 }
 
 /// Core Complex Die - contains one or more CCX
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ccd {
     pub id: usize,
     pub package_id: usize,
     /// Logical CPU IDs belonging to this CCD
     pub cores: Vec<usize>,
+    /// Shared L3 cache size in KiB (if available)
+    pub l3_cache_kb: Option<u64>,
 }
 
 /// NUMA node grouping
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NumaNode {
     pub id: usize,
     /// Logical CPU IDs belonging to this NUMA node
@@ -51,7 +55,7 @@ pub struct NumaNode {
 }
 
 /// NPS (NUMA Per Socket) node
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NpsNode {
     pub id: usize,
     /// NUMA nodes in this NPS group
@@ -61,7 +65,7 @@ pub struct NpsNode {
 }
 
 /// AMD Zen generation
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ZenGeneration {
     Zen,
     Zen2,
@@ -85,7 +89,7 @@ impl std::fmt::Display for ZenGeneration {
 }
 
 /// NPS (NUMA Per Socket) mode
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NpsMode {
     Nps1,
     Nps2,
@@ -105,7 +109,7 @@ impl std::fmt::Display for NpsMode {
 }
 
 /// Complete system topology
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ZenTopology {
     pub cpu_model: String,
     pub generation: ZenGeneration,
@@ -137,3 +141,33 @@ impl Default for ZenTopology {
         }
     }
 }
+
+impl ZenTopology {
+    /// Entry of a logical CPU; CPU IDs need not match positions in `cores`
+    /// once CPUs are offline
+    pub fn core(&self, cpu_id: usize) -> Option<&CpuCore> {
+        self.cores.iter().find(|c| c.id == cpu_id)
+    }
+
+    /// CCD IDs covered by the given logical CPUs
+    pub fn ccds_for_cpus(&self, cpus: &[usize]) -> Vec<usize> {
+        let mut ccds: Vec<usize> = cpus
+            .iter()
+            .filter_map(|&cpu_id| self.core(cpu_id).map(|c| c.ccd_id))
+            .collect();
+        ccds.sort();
+        ccds.dedup();
+        ccds
+    }
+
+    /// NUMA node IDs covered by the given logical CPUs
+    pub fn numa_nodes_for_cpus(&self, cpus: &[usize]) -> Vec<usize> {
+        let mut nodes: Vec<usize> = cpus
+            .iter()
+            .filter_map(|&cpu_id| self.core(cpu_id).map(|c| c.numa_node))
+            .collect();
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+}
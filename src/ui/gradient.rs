@@ -0,0 +1,120 @@
+use clap::ValueEnum;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+/// Viridis control points at 0%, 12.5%, ... 100%
+const VIRIDIS: [(u8, u8, u8); 9] = [
+    (68, 1, 84),
+    (71, 44, 122),
+    (59, 81, 139),
+    (44, 113, 142),
+    (33, 144, 141),
+    (39, 173, 129),
+    (92, 200, 99),
+    (170, 220, 50),
+    (253, 231, 37),
+];
+
+/// Inferno control points at 0%, 12.5%, ... 100%
+const INFERNO: [(u8, u8, u8); 9] = [
+    (0, 0, 4),
+    (31, 12, 72),
+    (85, 15, 109),
+    (136, 34, 106),
+    (186, 54, 85),
+    (227, 89, 51),
+    (249, 140, 10),
+    (249, 201, 50),
+    (252, 255, 164),
+];
+
+/// Channel levels of the xterm 6x6x6 color cube (indexes 16-231)
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Continuous usage color scale for heatmaps, group headers and waterfalls
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Gradient {
+    /// Quantize to the theme's threshold colors
+    #[default]
+    Off,
+    /// Perceptually uniform purple to yellow
+    Viridis,
+    /// Perceptually uniform black to pale yellow
+    Inferno,
+}
+
+/// Colors the terminal can display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// The 16 named colors only
+    Basic,
+    /// xterm 256-color palette
+    Indexed,
+    /// 24-bit RGB
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Guess from `COLORTERM` and `TERM`, the variables terminals actually set
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if !colorterm.is_empty() || term.contains("256color") {
+            ColorDepth::Indexed
+        } else {
+            ColorDepth::Basic
+        }
+    }
+}
+
+impl Gradient {
+    /// RGB for a usage percentage, interpolated between control points
+    pub fn rgb(self, usage: f32) -> Option<(u8, u8, u8)> {
+        let stops = match self {
+            Gradient::Off => return None,
+            Gradient::Viridis => &VIRIDIS,
+            Gradient::Inferno => &INFERNO,
+        };
+        let pos = (usage.clamp(0.0, 100.0) / 100.0) * (stops.len() - 1) as f32;
+        let idx = (pos as usize).min(stops.len() - 2);
+        let t = pos - idx as f32;
+        let (a, b) = (stops[idx], stops[idx + 1]);
+        let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+        Some((mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2)))
+    }
+
+    /// Terminal color for a usage percentage, or `None` if the gradient is off
+    /// or the terminal only has the basic palette
+    pub fn color(self, usage: f32, depth: ColorDepth) -> Option<Color> {
+        let (r, g, b) = self.rgb(usage)?;
+        match depth {
+            ColorDepth::TrueColor => Some(Color::Rgb(r, g, b)),
+            ColorDepth::Indexed => Some(Color::Indexed(
+                16 + 36 * cube_index(r) + 6 * cube_index(g) + cube_index(b),
+            )),
+            ColorDepth::Basic => None,
+        }
+    }
+}
+
+/// Nearest color cube level for a channel value
+fn cube_index(value: u8) -> u8 {
+    CUBE_LEVELS
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &level)| (level as i16 - value as i16).abs())
+        .map_or(0, |(idx, _)| idx as u8)
+}
+
+/// Black or white, whichever reads better on the given background
+pub fn contrast_text(r: u8, g: u8, b: u8) -> Color {
+    let luminance = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    if luminance > 140.0 {
+        Color::Black
+    } else {
+        Color::White
+    }
+}
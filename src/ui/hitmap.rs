@@ -0,0 +1,238 @@
+use std::cell::{Cell, RefCell};
+use std::mem;
+
+use ratatui::layout::{Position, Rect};
+
+use crate::app::Scroll;
+
+/// What a mouse click on a screen region acts on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// CPU bar, heatmap cell or sparkline/waterfall row
+    Cpu(usize),
+    /// CCD or NPS group header (group ID)
+    Group(usize),
+    /// Socket pane of the split view
+    Socket(usize),
+    /// Host row of the cluster overview (position in the host list)
+    Host(usize),
+    /// "View:" label in the header status line
+    ViewLabel,
+    /// "Cores:" label in the header status line
+    SmtLabel,
+    /// "Mode:" label in the header status line
+    ModeLabel,
+}
+
+/// Where a selectable item sits in its view's scrollable content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    /// Pane the item is drawn in (see `App::scroll`)
+    pub pane: usize,
+    /// First line from the top of the content, in the view's scroll units
+    pub line: usize,
+    /// Lines the item spans
+    pub height: usize,
+    /// Lines the view shows at once
+    pub viewport: usize,
+}
+
+/// Clickable regions, item placements and scroll extents recorded while
+/// drawing a frame
+///
+/// Widgets and views only get shared references while rendering, so
+/// everything is collected behind a `RefCell`. Placements cover items
+/// scrolled out of view too, so the cursor can be kept on screen.
+#[derive(Debug, Default)]
+pub struct HitMap {
+    regions: RefCell<Vec<(Rect, Target)>>,
+    placements: RefCell<Vec<(Target, Placement)>>,
+    /// Pane currently being drawn
+    pane: Cell<usize>,
+    scrolls: RefCell<[Option<Scroll>; 2]>,
+    /// Scroll extent of the help overlay, when shown
+    help: Cell<Option<Scroll>>,
+}
+
+impl HitMap {
+    /// Register a region; regions added later sit on top of earlier ones
+    pub fn add(&self, area: Rect, target: Target) {
+        self.regions.borrow_mut().push((area, target));
+    }
+
+    /// Record the content lines an item occupies, whether drawn or not
+    pub fn place(&self, target: Target, line: usize, height: usize, viewport: usize) {
+        let placement = Placement {
+            pane: self.pane.get(),
+            line,
+            height,
+            viewport,
+        };
+        self.placements.borrow_mut().push((target, placement));
+    }
+
+    /// Attribute placements and scroll extents recorded from now on to a pane
+    pub fn set_pane(&self, pane: usize) {
+        self.pane.set(pane);
+    }
+
+    /// Clamp a view's scroll offset to its content and record the result
+    /// for the scrollbar and the next scroll key; returns the clamped offset
+    pub fn scroll(&self, offset: usize, content: usize, viewport: usize) -> usize {
+        let offset = offset.min(content.saturating_sub(viewport));
+        self.scrolls.borrow_mut()[self.pane.get()] = Some(Scroll {
+            offset,
+            content,
+            viewport,
+        });
+        offset
+    }
+
+    /// Scroll state a pane was drawn with, if its view scrolls
+    pub fn scroll_of(&self, pane: usize) -> Option<Scroll> {
+        self.scrolls.borrow()[pane]
+    }
+
+    /// Like `scroll`, for the help overlay drawn on top of the panes
+    pub fn help_scroll(&self, offset: usize, content: usize, viewport: usize) -> usize {
+        let offset = offset.min(content.saturating_sub(viewport));
+        self.help.set(Some(Scroll {
+            offset,
+            content,
+            viewport,
+        }));
+        offset
+    }
+
+    /// Scroll state the help overlay was drawn with, if it was shown
+    pub fn help_scroll_of(&self) -> Option<Scroll> {
+        self.help.get()
+    }
+
+    /// Targets under a screen cell, topmost first
+    pub fn targets_at(&self, column: u16, row: u16) -> Vec<Target> {
+        let position = Position::new(column, row);
+        self.regions
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|(area, _)| area.contains(position))
+            .map(|(_, target)| *target)
+            .collect()
+    }
+
+    /// Where an item sits in its view's content
+    pub fn placement(&self, target: Target) -> Option<Placement> {
+        self.placements
+            .borrow()
+            .iter()
+            .find(|(t, _)| *t == target)
+            .map(|(_, placement)| *placement)
+    }
+
+    /// Item of the same kind about `lines` content lines above or below
+    /// `from`, at the same position within its line
+    ///
+    /// Unlike `neighbor` this also finds items scrolled out of view, so
+    /// paging works past the edge of the screen.
+    pub fn shifted(&self, from: Target, lines: isize) -> Option<Target> {
+        let placements = self.placements.borrow();
+        let same_pane = Self::same_pane(&placements, from)?;
+        let origin = same_pane.iter().find(|(t, _)| *t == from)?.1;
+        let column = same_pane
+            .iter()
+            .filter(|(_, p)| p.line == origin.line)
+            .position(|(t, _)| *t == from)?;
+
+        let wanted = origin.line.saturating_add_signed(lines);
+        let line = same_pane
+            .iter()
+            .map(|(_, p)| p.line)
+            .min_by_key(|line| line.abs_diff(wanted))?;
+        let on_line: Vec<Target> = same_pane
+            .iter()
+            .filter(|(_, p)| p.line == line)
+            .map(|(t, _)| *t)
+            .collect();
+        on_line.get(column.min(on_line.len() - 1)).copied()
+    }
+
+    /// First or last item of the same kind in the pane of `from`
+    pub fn edge(&self, from: Target, last: bool) -> Option<Target> {
+        let placements = self.placements.borrow();
+        let same_pane = Self::same_pane(&placements, from)?;
+        let edge = if last {
+            same_pane.last()
+        } else {
+            same_pane.first()
+        };
+        edge.map(|(t, _)| *t)
+    }
+
+    /// Placements of the same kind as `from` in its pane, in content order
+    fn same_pane(
+        placements: &[(Target, Placement)],
+        from: Target,
+    ) -> Option<Vec<(Target, Placement)>> {
+        let pane = placements.iter().find(|(t, _)| *t == from)?.1.pane;
+        let mut same: Vec<_> = placements
+            .iter()
+            .filter(|(t, p)| p.pane == pane && mem::discriminant(t) == mem::discriminant(&from))
+            .copied()
+            .collect();
+        // Stable, so items sharing a line keep their left-to-right order
+        same.sort_by_key(|(_, p)| p.line);
+        Some(same)
+    }
+
+    /// Nearest drawn item of the same kind on the closest row above or below
+    ///
+    /// Only looks within the socket pane `from` is drawn in, so the cursor
+    /// stays in its column of a grid and does not jump between panes.
+    pub fn neighbor(&self, from: Target, down: bool) -> Option<Target> {
+        let regions = self.regions.borrow();
+        let (origin, _) = *regions.iter().find(|(_, t)| *t == from)?;
+        let pane = Self::pane_of(&regions, origin);
+        regions
+            .iter()
+            .filter(|(area, target)| {
+                *target != from
+                    && mem::discriminant(target) == mem::discriminant(&from)
+                    && pane.iter().all(|pane| pane.contains(area.as_position()))
+                    && if down {
+                        area.y > origin.y
+                    } else {
+                        area.y < origin.y
+                    }
+            })
+            .min_by_key(|(area, _)| (area.y.abs_diff(origin.y), area.x.abs_diff(origin.x)))
+            .map(|(_, target)| *target)
+    }
+
+    /// Drawn items of the same kind on the row of `from`, itself included
+    pub fn row_len(&self, from: Target) -> usize {
+        let regions = self.regions.borrow();
+        let Some(&(origin, _)) = regions.iter().find(|(_, t)| *t == from) else {
+            return 0;
+        };
+        let pane = Self::pane_of(&regions, origin);
+        regions
+            .iter()
+            .filter(|(area, target)| {
+                mem::discriminant(target) == mem::discriminant(&from)
+                    && area.y == origin.y
+                    && pane.iter().all(|pane| pane.contains(area.as_position()))
+            })
+            .count()
+    }
+
+    /// Socket pane containing a region, if the view is split
+    fn pane_of(regions: &[(Rect, Target)], area: Rect) -> Option<Rect> {
+        regions
+            .iter()
+            .find(|(pane, target)| {
+                matches!(target, Target::Socket(_)) && pane.contains(area.as_position())
+            })
+            .map(|(pane, _)| *pane)
+    }
+}
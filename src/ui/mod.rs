@@ -1,3 +1,5 @@
+pub mod gradient;
+pub mod hitmap;
 pub mod layout;
 pub mod renderer;
 pub mod theme;
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
@@ -7,16 +9,34 @@ use ratatui::{
 };
 
 use crate::app::{App, ViewMode};
+use crate::ui::hitmap::{HitMap, Target};
 use crate::ui::theme::Theme;
-use crate::ui::views::{render_ccd_view, render_core_view, render_nps_view};
-use crate::ui::widgets::{Header, HelpOverlay};
+use crate::ui::views::{
+    irq_view, render_ccd_view, render_cgroup_view, render_cluster_view, render_core_view,
+    render_irq_view, render_nps_view, ViewContext,
+};
+use crate::stats::GroupKind;
+use crate::ui::widgets::{
+    DetailPanel, GuestPanel, Header, HelpOverlay, HistoryChart, ScrollIndicator,
+    DETAIL_PANEL_WIDTH,
+};
 
-/// Render the application UI
-pub fn render(frame: &mut Frame, app: &App, theme: &Theme) {
+/// Render the application UI, recording clickable regions in `hits`
+pub fn render(frame: &mut Frame, app: &App, theme: &Theme, hits: &HitMap) {
     let area = frame.area();
 
-    // Create layout: header (2 lines) + content
-    let chunks = Layout::vertical([Constraint::Length(2), Constraint::Min(1)]).split(area);
+    // Create layout: header (2 lines) + content (+ guest panel)
+    let guest_panel_height = if app.show_vcpus {
+        GuestPanel::height(app.guests.len())
+    } else {
+        0
+    };
+    let chunks = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Min(1),
+        Constraint::Length(guest_panel_height),
+    ])
+    .split(area);
 
     // Render header
     let header = Header::new(
@@ -26,22 +46,61 @@ pub fn render(frame: &mut Frame, app: &App, theme: &Theme) {
         app.show_smt,
         app.stats.total_usage,
         theme,
-    );
+    )
+    .refresh(app.refresh_interval, app.frozen)
+    .steal_warning(app.steal_warning())
+    .source_status(app.notice().map(String::from).or_else(|| app.source_status()))
+    .hit_map(hits);
     frame.render_widget(header, chunks[0]);
 
-    // Render main content based on view mode
-    let content_area = chunks[1];
+    // Render main content based on view mode, with the detail panel on the right
+    let (content_area, detail_area) = if app.show_detail && app.has_cursor() {
+        let columns = Layout::horizontal([
+            Constraint::Min(1),
+            Constraint::Length(DETAIL_PANEL_WIDTH),
+        ])
+        .split(chunks[1]);
+        (columns[0], Some(columns[1]))
+    } else {
+        (chunks[1], None)
+    };
 
     // Use a custom widget to render the view
     let view_widget = ViewWidget {
         app,
         theme,
+        labels: app.vcpu_labels(),
+        hits,
     };
     frame.render_widget(view_widget, content_area);
 
+    if let Some(area) = detail_area {
+        if let Some(core) = app.core(app.selected_cpu) {
+            let series = app.history.cpu_series(core.id);
+            let processes = app.details.processes.get(&core.id);
+            let panel = DetailPanel::new(core, theme)
+                .usage(app.stats.usage(core.id))
+                .breakdown(app.details.breakdowns.get(&core.id))
+                .temperature(app.details.temperatures.cpu(&app.topology, core.id))
+                .history(&series)
+                .processes(processes.map_or(&[], Vec::as_slice))
+                .local(app.is_live());
+            frame.render_widget(panel, area);
+        }
+    }
+
+    if app.show_vcpus {
+        frame.render_widget(GuestPanel::new(&app.topology, &app.guests, theme), chunks[2]);
+    }
+
     // Render help overlay if active
     if app.show_help {
-        frame.render_widget(HelpOverlay::new(theme), area);
+        frame.render_widget(
+            HelpOverlay::new(theme)
+                .scroll(app.help_scroll.offset)
+                .hit_map(hits),
+            area,
+        );
     }
 }
 
@@ -49,39 +108,93 @@ pub fn render(frame: &mut Frame, app: &App, theme: &Theme) {
 struct ViewWidget<'a> {
     app: &'a App,
     theme: &'a Theme,
+    /// Guest vCPU labels keyed by host CPU
+    labels: HashMap<usize, String>,
+    hits: &'a HitMap,
 }
 
 impl Widget for ViewWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if self.app.topology.packages == 2 {
+        if self.app.show_chart && self.render_chart(area, buf) {
+            return;
+        }
+
+        // cgroup, IRQ and cluster views span sockets, so they always use the full area
+        let socket_split = !matches!(
+            self.app.view_mode,
+            ViewMode::Cgroup | ViewMode::Irq | ViewMode::Cluster
+        );
+        if let Some(socket) = self.app.socket.filter(|_| socket_split) {
+            // --socket: one pane for the requested socket
+            self.render_socket(area, buf, Some(socket), 0);
+        } else if self.app.topology.packages == 2 && socket_split {
             // 2-socket system: split screen vertically
             let half_width = area.width / 2;
             let left_area = Rect::new(area.x, area.y, half_width, area.height);
             let right_area = Rect::new(area.x + half_width, area.y, area.width - half_width, area.height);
 
             // Render both sockets
-            self.render_socket(left_area, buf, Some(0));
-            self.render_socket(right_area, buf, Some(1));
+            self.render_socket(left_area, buf, Some(0), 0);
+            self.render_socket(right_area, buf, Some(1), 1);
         } else {
             // Single socket or other: use full area
-            self.render_socket(area, buf, None);
+            self.render_socket(area, buf, None, 0);
         }
     }
 }
 
 impl ViewWidget<'_> {
-    /// Render a socket's content with border frame
-    fn render_socket(&self, area: Rect, buf: &mut Buffer, socket_filter: Option<usize>) {
+    /// Render the history chart for the selected CPU or group
+    ///
+    /// Returns false if the current view has nothing to chart.
+    fn render_chart(&self, area: Rect, buf: &mut Buffer) -> bool {
+        let history = &self.app.history;
+        let (title, series) = match (self.app.view_mode, self.app.selected_group_id()) {
+            (ViewMode::Core, _) => (
+                format!("CPU {} history", self.app.selected_cpu),
+                history.cpu_series(self.app.selected_cpu),
+            ),
+            (ViewMode::Ccd, Some(id)) => (
+                format!("CCD {} history", id),
+                history.group_series(GroupKind::Ccd, id),
+            ),
+            (ViewMode::Nps, Some(id)) => (
+                format!("NPS {} history", id),
+                history.group_series(GroupKind::Nps, id),
+            ),
+            _ => return false,
+        };
+
+        let offsets = history.time_offsets();
+        HistoryChart::new(&title, &series, &offsets, self.theme).render(area, buf);
+        true
+    }
+
+    /// Render a socket's content with border frame into scroll pane `pane`
+    fn render_socket(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        socket_filter: Option<usize>,
+        pane: usize,
+    ) {
         if area.height == 0 || area.width == 0 {
             return;
         }
 
-        // Create block with border
+        // Create block with border; the focused pane of a split view is highlighted
         let block = if let Some(socket_id) = socket_filter {
+            self.hits.add(area, Target::Socket(socket_id));
+            let focused = self.app.socket.is_none() && socket_id == self.app.focused_socket;
+            let border = if focused {
+                self.theme.text_highlight
+            } else {
+                self.theme.border
+            };
             Block::default()
                 .borders(Borders::ALL)
                 .title(format!(" Socket {} ", socket_id))
-                .border_style(Style::default().fg(self.theme.border))
+                .border_style(Style::default().fg(border))
                 .title_style(
                     Style::default()
                         .fg(self.theme.text_highlight)
@@ -101,46 +214,70 @@ impl ViewWidget<'_> {
             return;
         }
 
+        self.hits.set_pane(pane);
+        let scroll_offset = self.app.scroll[pane].offset;
+        let ctx = ViewContext {
+            topology: &self.app.topology,
+            stats: &self.app.stats,
+            history: &self.app.history,
+            labels: &self.labels,
+            show_smt: self.app.show_smt,
+            scroll_offset,
+            selected_group: self.app.selected_group_id(),
+            selected_cpu: self.app.selected_cpu,
+            display_mode: self.app.display_mode,
+            columns: self.app.columns,
+            theme: self.theme,
+            socket_filter,
+            cpus: self.app.cpus.as_deref(),
+            hits: self.hits,
+        };
         match self.app.view_mode {
-            ViewMode::Core => {
-                render_core_view(
+            ViewMode::Core => render_core_view(inner_area, buf, &ctx),
+            ViewMode::Ccd => render_ccd_view(inner_area, buf, &ctx),
+            ViewMode::Nps => render_nps_view(inner_area, buf, &ctx),
+            ViewMode::Cgroup => {
+                render_cgroup_view(
                     inner_area,
                     buf,
                     &self.app.topology,
-                    &self.app.stats,
-                    self.app.show_smt,
-                    self.app.scroll_offset,
-                    self.app.display_mode,
+                    &self.app.cgroups,
+                    scroll_offset,
                     self.theme,
-                    socket_filter,
+                    self.hits,
                 );
             }
-            ViewMode::Ccd => {
-                render_ccd_view(
-                    inner_area,
-                    buf,
-                    &self.app.topology,
-                    &self.app.stats,
-                    self.app.show_smt,
-                    self.app.scroll_offset,
-                    self.app.display_mode,
-                    self.theme,
-                    socket_filter,
-                );
+            ViewMode::Irq => {
+                // The IRQ view spans both sockets, so --socket filters its rows instead
+                let ctx = ViewContext {
+                    socket_filter: self.app.socket,
+                    ..ctx
+                };
+                render_irq_view(inner_area, buf, &self.app.interrupts, &ctx);
             }
-            ViewMode::Nps => {
-                render_nps_view(
+            ViewMode::Cluster => {
+                render_cluster_view(
                     inner_area,
                     buf,
-                    &self.app.topology,
-                    &self.app.stats,
-                    self.app.show_smt,
-                    self.app.scroll_offset,
-                    self.app.display_mode,
+                    &self.app.cluster_hosts(),
+                    self.app.selected_host,
+                    scroll_offset,
                     self.theme,
-                    socket_filter,
+                    self.hits,
                 );
             }
         }
+
+        if let Some(scroll) = self.hits.scroll_of(pane) {
+            // The IRQ view only scrolls its rate list, which the detail's top
+            // border closes off
+            let area = if self.app.view_mode == ViewMode::Irq {
+                let list = irq_view::rate_list_area(inner_area);
+                Rect::new(area.x, area.y, area.width, list.height + 2)
+            } else {
+                area
+            };
+            ScrollIndicator::new(scroll, self.theme).render(area, buf);
+        }
     }
 }
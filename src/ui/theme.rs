@@ -1,47 +1,270 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
 use ratatui::style::{Color, Style};
+use serde::Deserialize;
+
+use crate::settings::Settings;
+use crate::ui::gradient::{contrast_text, ColorDepth, Gradient};
+
+/// Built-in theme names, in the order the theme key cycles through them
+pub const BUILTIN_THEMES: [&str; 5] = ["default", "light", "mono", "deuteranopia", "protanopia"];
 
 /// Color scheme for CPU usage visualization
+#[derive(Debug, Clone)]
 pub struct Theme {
-    pub bar_low: Color,      // 0-25%
+    /// Built-in name or theme file stem
+    pub name: String,
+    pub bar_low: Color,      // 0-25% (below thresholds[0])
     pub bar_medium: Color,   // 25-50%
     pub bar_high: Color,     // 50-75%
-    pub bar_critical: Color, // 75-100%
+    pub bar_critical: Color, // 75-100% (from thresholds[2])
+    pub bar_steal: Color,    // Hypervisor steal time
     pub text_normal: Color,
     pub text_dim: Color,
     pub text_highlight: Color,
     pub border: Color,
     pub header_bg: Color,
     pub header_fg: Color,
+    /// Usage percentages where bars switch to medium, high and critical
+    pub thresholds: [f32; 3],
+    /// Continuous scale for heatmap cells, group headers and waterfalls
+    pub gradient: Gradient,
+    /// What the terminal can display; decides how gradients are rendered
+    pub color_depth: ColorDepth,
 }
 
 impl Default for Theme {
     fn default() -> Self {
         Self {
+            name: String::from("default"),
             bar_low: Color::Green,
             bar_medium: Color::Cyan,
             bar_high: Color::Yellow,
             bar_critical: Color::Red,
+            bar_steal: Color::Magenta,
             text_normal: Color::White,
             text_dim: Color::DarkGray,
             text_highlight: Color::Cyan,
             border: Color::DarkGray,
             header_bg: Color::Blue,
             header_fg: Color::White,
+            thresholds: [25.0, 50.0, 75.0],
+            gradient: Gradient::Off,
+            color_depth: ColorDepth::Basic,
         }
     }
 }
 
+/// Theme file contents; every key is optional and falls back to `base`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    /// Built-in theme to start from (default: "default")
+    base: Option<String>,
+    thresholds: Option<[f32; 3]>,
+    gradient: Option<Gradient>,
+    #[serde(default)]
+    colors: ThemeColors,
+}
+
+/// `[colors]` table: names ("red", "darkgray"), "#rrggbb" or 256-color indexes
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeColors {
+    bar_low: Option<String>,
+    bar_medium: Option<String>,
+    bar_high: Option<String>,
+    bar_critical: Option<String>,
+    bar_steal: Option<String>,
+    text_normal: Option<String>,
+    text_dim: Option<String>,
+    text_highlight: Option<String>,
+    border: Option<String>,
+    header_bg: Option<String>,
+    header_fg: Option<String>,
+}
+
 impl Theme {
+    /// Look up a built-in theme by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Self::default(),
+            // Darker bars and black text for light terminal backgrounds
+            "light" => Self {
+                bar_low: Color::Indexed(28),
+                bar_medium: Color::Indexed(31),
+                bar_high: Color::Indexed(130),
+                bar_critical: Color::Indexed(124),
+                bar_steal: Color::Indexed(127),
+                text_normal: Color::Black,
+                text_dim: Color::Indexed(244),
+                text_highlight: Color::Indexed(25),
+                border: Color::Indexed(248),
+                header_bg: Color::Indexed(25),
+                header_fg: Color::White,
+                ..Self::default()
+            },
+            // Terminal defaults only, for NO_COLOR and monochrome terminals
+            "mono" => Self {
+                bar_low: Color::Reset,
+                bar_medium: Color::Reset,
+                bar_high: Color::Reset,
+                bar_critical: Color::Reset,
+                bar_steal: Color::Reset,
+                text_normal: Color::Reset,
+                text_dim: Color::Reset,
+                text_highlight: Color::Reset,
+                border: Color::Reset,
+                header_bg: Color::Reset,
+                header_fg: Color::Reset,
+                ..Self::default()
+            },
+            // Okabe-Ito blue to vermillion; avoids the red/green axis
+            "deuteranopia" => Self {
+                bar_low: Color::Indexed(25),
+                bar_medium: Color::Indexed(74),
+                bar_high: Color::Indexed(214),
+                bar_critical: Color::Indexed(166),
+                bar_steal: Color::Indexed(175),
+                ..Self::default()
+            },
+            // Blue to yellow; reds look dark to protans, so brightness rises with load
+            "protanopia" => Self {
+                bar_low: Color::Indexed(25),
+                bar_medium: Color::Indexed(74),
+                bar_high: Color::Indexed(172),
+                bar_critical: Color::Indexed(226),
+                bar_steal: Color::White,
+                ..Self::default()
+            },
+            _ => return None,
+        };
+        Some(Self {
+            name: name.to_string(),
+            ..theme
+        })
+    }
+
+    /// Resolve a `--theme` value: a built-in, a file path, or a file in the themes directory
+    pub fn load(spec: &str) -> Result<Self> {
+        if let Some(theme) = Self::builtin(spec) {
+            return Ok(theme);
+        }
+        if spec.contains('/') || spec.ends_with(".toml") {
+            return Self::from_file(Path::new(spec));
+        }
+        if let Some(path) = Settings::themes_dir().map(|dir| dir.join(format!("{}.toml", spec))) {
+            if path.exists() {
+                return Self::from_file(&path);
+            }
+        }
+        bail!(
+            "Unknown theme '{}' (built-in: {})",
+            spec,
+            BUILTIN_THEMES.join(", ")
+        )
+    }
+
+    /// Read a theme file; the theme is named after the file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme {}", path.display()))?;
+        let file: ThemeFile = toml::from_str(&content)
+            .with_context(|| format!("Invalid theme file {}", path.display()))?;
+
+        let base = file.base.as_deref().unwrap_or("default");
+        let Some(mut theme) = Self::builtin(base) else {
+            bail!("Unknown base theme '{}' in {}", base, path.display());
+        };
+        theme.name = path
+            .file_stem()
+            .map_or_else(|| base.to_string(), |s| s.to_string_lossy().into_owned());
+
+        let colors = &file.colors;
+        for (slot, value) in [
+            (&mut theme.bar_low, &colors.bar_low),
+            (&mut theme.bar_medium, &colors.bar_medium),
+            (&mut theme.bar_high, &colors.bar_high),
+            (&mut theme.bar_critical, &colors.bar_critical),
+            (&mut theme.bar_steal, &colors.bar_steal),
+            (&mut theme.text_normal, &colors.text_normal),
+            (&mut theme.text_dim, &colors.text_dim),
+            (&mut theme.text_highlight, &colors.text_highlight),
+            (&mut theme.border, &colors.border),
+            (&mut theme.header_bg, &colors.header_bg),
+            (&mut theme.header_fg, &colors.header_fg),
+        ] {
+            if let Some(value) = value {
+                *slot = Color::from_str(value).map_err(|_| {
+                    anyhow::anyhow!("Invalid color '{}' in {}", value, path.display())
+                })?;
+            }
+        }
+
+        if let Some(thresholds) = file.thresholds {
+            if !thresholds.windows(2).all(|w| w[0] <= w[1]) {
+                bail!("Theme thresholds must be ascending in {}", path.display());
+            }
+            theme.thresholds = thresholds;
+        }
+        if let Some(gradient) = file.gradient {
+            theme.gradient = gradient;
+        }
+        Ok(theme)
+    }
+
+    /// Themes the cycle key steps through: built-ins, then files in the themes directory
+    ///
+    /// Unreadable theme files are skipped here; selecting one explicitly
+    /// reports the error.
+    pub fn available() -> Vec<Self> {
+        let mut themes: Vec<Self> = BUILTIN_THEMES
+            .iter()
+            .filter_map(|n| Self::builtin(n))
+            .collect();
+
+        let mut paths: Vec<_> = Settings::themes_dir()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+        themes.extend(paths.iter().filter_map(|path| Self::from_file(path).ok()));
+        themes
+    }
+
     /// Get color for usage percentage
     pub fn usage_color(&self, usage: f32) -> Color {
+        let [medium, high, critical] = self.thresholds;
         match usage {
-            u if u < 25.0 => self.bar_low,
-            u if u < 50.0 => self.bar_medium,
-            u if u < 75.0 => self.bar_high,
+            u if u < medium => self.bar_low,
+            u if u < high => self.bar_medium,
+            u if u < critical => self.bar_high,
             _ => self.bar_critical,
         }
     }
 
+    /// Fill color for heatmap-style cells: the gradient when the terminal can
+    /// show it, otherwise the threshold colors
+    pub fn heat_color(&self, usage: f32) -> Color {
+        self.gradient
+            .color(usage, self.color_depth)
+            .unwrap_or_else(|| self.usage_color(usage))
+    }
+
+    /// Text color that stays readable on `heat_color(usage)` backgrounds
+    pub fn heat_text_color(&self, usage: f32) -> Color {
+        match self.gradient.rgb(usage) {
+            Some((r, g, b)) if self.color_depth != ColorDepth::Basic => contrast_text(r, g, b),
+            _ => self.header_fg,
+        }
+    }
+
     /// Get style for usage bar
     pub fn bar_style(&self, usage: f32) -> Style {
         Style::default().fg(self.usage_color(usage))
@@ -1,41 +1,36 @@
 use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
 
 use crate::app::DisplayMode;
-use crate::stats::{GroupUsage, SystemStats};
-use crate::topology::ZenTopology;
+use crate::stats::{GroupKind, GroupUsage};
+use crate::ui::hitmap::Target;
 use crate::ui::layout::GridLayout;
-use crate::ui::theme::Theme;
-use crate::ui::widgets::{CompactCpuBar, CpuBar, GroupHeader, HeatmapCell};
+use crate::ui::views::waterfall::{render_waterfall, WaterfallRow};
+use crate::ui::views::ViewContext;
+use crate::ui::widgets::{CompactCpuBar, CpuBar, GroupHeader, HeatmapCell, UsageSparkline};
 
 /// Render CCD grouped view
-pub fn render_ccd_view(
-    area: Rect,
-    buf: &mut Buffer,
-    topology: &ZenTopology,
-    stats: &SystemStats,
-    show_smt: bool,
-    scroll_offset: usize,
-    display_mode: DisplayMode,
-    theme: &Theme,
-    socket_filter: Option<usize>,
-) {
-    if area.height == 0 || topology.ccds.is_empty() {
+pub fn render_ccd_view(area: Rect, buf: &mut Buffer, ctx: &ViewContext) {
+    if area.height == 0 || ctx.topology.ccds.is_empty() {
         return;
     }
 
     // Build group usages (filtered by socket if specified)
     let mut groups: Vec<GroupUsage> = Vec::new();
 
-    for ccd in topology.ccds.iter().filter(|c| socket_filter.map_or(true, |s| c.package_id == s)) {
-        let cores_in_ccd: Vec<_> = if show_smt {
+    for ccd in ctx
+        .topology
+        .ccds
+        .iter()
+        .filter(|c| ctx.socket_filter.map_or(true, |s| c.package_id == s))
+    {
+        let mut cores_in_ccd: Vec<_> = if ctx.show_smt {
             ccd.cores.clone()
         } else {
             ccd.cores
                 .iter()
                 .filter(|&&cpu_id| {
-                    topology
-                        .cores
-                        .get(cpu_id)
+                    ctx.topology
+                        .core(cpu_id)
                         .map(|c| c.smt_sibling.map_or(true, |s| c.id < s))
                         .unwrap_or(true)
                 })
@@ -43,15 +38,17 @@ pub fn render_ccd_view(
                 .collect()
         };
 
+        // Groups without any of the requested CPUs are hidden entirely
+        if let Some(cpus) = ctx.cpus {
+            cores_in_ccd.retain(|cpu_id| cpus.contains(cpu_id));
+            if cores_in_ccd.is_empty() {
+                continue;
+            }
+        }
+
         let usages: Vec<_> = cores_in_ccd
             .iter()
-            .filter_map(|&cpu_id| {
-                stats.core_usages.get(cpu_id).map(|u| crate::stats::CoreUsage {
-                    core_id: cpu_id,
-                    usage_percent: u.usage_percent,
-                    frequency_mhz: u.frequency_mhz,
-                })
-            })
+            .filter_map(|&cpu_id| ctx.stats.usage(cpu_id).cloned())
             .collect();
 
         groups.push(GroupUsage::from_cores(
@@ -61,7 +58,7 @@ pub fn render_ccd_view(
         ));
     }
 
-    render_grouped_view(area, buf, &groups, scroll_offset, display_mode, theme);
+    render_grouped_view(area, buf, &groups, GroupKind::Ccd, ctx);
 }
 
 /// Common rendering for grouped views
@@ -69,35 +66,51 @@ pub fn render_grouped_view(
     area: Rect,
     buf: &mut Buffer,
     groups: &[GroupUsage],
-    scroll_offset: usize,
-    display_mode: DisplayMode,
-    theme: &Theme,
+    kind: GroupKind,
+    ctx: &ViewContext,
 ) {
     if area.height == 0 {
         return;
     }
 
-    match display_mode {
+    match ctx.display_mode {
         DisplayMode::Full => {
-            render_grouped_full(area, buf, groups, scroll_offset, theme);
+            render_grouped_full(area, buf, groups, ctx);
         }
         DisplayMode::Compact => {
-            render_grouped_compact(area, buf, groups, scroll_offset, theme);
+            render_grouped_compact(area, buf, groups, ctx);
         }
         DisplayMode::Heatmap => {
-            render_grouped_heatmap(area, buf, groups, scroll_offset, theme);
+            render_grouped_heatmap(area, buf, groups, ctx);
+        }
+        DisplayMode::Sparkline => {
+            render_grouped_sparkline(area, buf, groups, kind, ctx);
+        }
+        DisplayMode::Waterfall => {
+            // One row per group, averaged over the (SMT-filtered) member CPUs
+            let rows: Vec<WaterfallRow> = groups
+                .iter()
+                .map(|group| {
+                    let cpu_ids: Vec<usize> =
+                        group.member_usages.iter().map(|u| u.core_id).collect();
+                    WaterfallRow::from_cpus(
+                        group.name.clone(),
+                        Target::Group(group.id),
+                        &cpu_ids,
+                        ctx.history,
+                    )
+                })
+                .collect();
+            let selected = ctx
+                .selected_group
+                .map_or(Target::Cpu(ctx.selected_cpu), Target::Group);
+            render_waterfall(area, buf, &rows, selected, ctx);
         }
     }
 }
 
 /// Full mode: 1 core per line within each group
-fn render_grouped_full(
-    area: Rect,
-    buf: &mut Buffer,
-    groups: &[GroupUsage],
-    scroll_offset: usize,
-    theme: &Theme,
-) {
+fn render_grouped_full(area: Rect, buf: &mut Buffer, groups: &[GroupUsage], ctx: &ViewContext) {
     // Calculate total lines needed
     let mut total_lines = 0;
     for group in groups {
@@ -107,28 +120,47 @@ fn render_grouped_full(
     }
 
     let visible_height = area.height as usize;
-    let max_scroll = total_lines.saturating_sub(visible_height);
-    let scroll = scroll_offset.min(max_scroll);
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, total_lines, visible_height);
 
     let mut current_line = 0;
     let mut y = area.y;
 
     for group in groups {
         // Group header
+        ctx.hits
+            .place(Target::Group(group.id), current_line, 1, visible_height);
         if current_line >= scroll && y < area.y + area.height {
             let header_area = Rect::new(area.x, y, area.width, 1);
-            GroupHeader::new(&group.name, group.usage_percent, group.core_count, theme)
-                .render(header_area, buf);
+            ctx.hits.add(header_area, Target::Group(group.id));
+            GroupHeader::new(
+                &group.name,
+                group.usage_percent,
+                group.core_count,
+                ctx.theme,
+            )
+            .selected(ctx.selected_group == Some(group.id))
+            .render(header_area, buf);
             y += 1;
         }
         current_line += 1;
 
         // Core bars
         for usage in &group.member_usages {
+            ctx.hits
+                .place(Target::Cpu(usage.core_id), current_line, 1, visible_height);
             if current_line >= scroll && y < area.y + area.height {
                 let label = format!("CPU{:2}", usage.core_id);
                 let bar_area = Rect::new(area.x, y, area.width, 1);
-                CpuBar::new(&label, usage.usage_percent, theme).render(bar_area, buf);
+                ctx.hits.add(bar_area, Target::Cpu(usage.core_id));
+                CpuBar::new(&label, usage.usage_percent, ctx.theme)
+                    .columns(ctx.columns)
+                    .virt(usage.steal_percent, usage.guest_percent)
+                    .frequency(usage.frequency_mhz)
+                    .selected(usage.core_id == ctx.selected_cpu)
+                    .annotation(ctx.labels.get(&usage.core_id).map(String::as_str))
+                    .render(bar_area, buf);
                 y += 1;
             }
             current_line += 1;
@@ -143,13 +175,7 @@ fn render_grouped_full(
 }
 
 /// Compact mode: multi-column within each group
-fn render_grouped_compact(
-    area: Rect,
-    buf: &mut Buffer,
-    groups: &[GroupUsage],
-    scroll_offset: usize,
-    theme: &Theme,
-) {
+fn render_grouped_compact(area: Rect, buf: &mut Buffer, groups: &[GroupUsage], ctx: &ViewContext) {
     let layout = GridLayout::compact(area, 16); // Use 16 cols
 
     // Calculate total lines needed
@@ -162,18 +188,28 @@ fn render_grouped_compact(
     }
 
     let visible_height = area.height as usize;
-    let max_scroll = total_lines.saturating_sub(visible_height);
-    let scroll = scroll_offset.min(max_scroll);
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, total_lines, visible_height);
 
     let mut current_line = 0;
     let mut y = area.y;
 
     for group in groups {
         // Group header
+        ctx.hits
+            .place(Target::Group(group.id), current_line, 1, visible_height);
         if current_line >= scroll && y < area.y + area.height {
             let header_area = Rect::new(area.x, y, area.width, 1);
-            GroupHeader::new(&group.name, group.usage_percent, group.core_count, theme)
-                .render(header_area, buf);
+            ctx.hits.add(header_area, Target::Group(group.id));
+            GroupHeader::new(
+                &group.name,
+                group.usage_percent,
+                group.core_count,
+                ctx.theme,
+            )
+            .selected(ctx.selected_group == Some(group.id))
+            .render(header_area, buf);
             y += 1;
         }
         current_line += 1;
@@ -181,6 +217,11 @@ fn render_grouped_compact(
         // Core bars in multi-column
         let rows_for_cores = (group.member_usages.len() + layout.cols - 1) / layout.cols;
         for row in 0..rows_for_cores {
+            let members = group.member_usages.iter().skip(row * layout.cols).take(layout.cols);
+            for usage in members {
+                ctx.hits
+                    .place(Target::Cpu(usage.core_id), current_line, 1, visible_height);
+            }
             if current_line >= scroll && y < area.y + area.height {
                 for col in 0..layout.cols {
                     let idx = row * layout.cols + col;
@@ -190,7 +231,11 @@ fn render_grouped_compact(
                     let usage = &group.member_usages[idx];
                     let x = area.x + (col as u16 * layout.cell_width);
                     let cell_area = Rect::new(x, y, layout.cell_width, 1);
-                    CompactCpuBar::new(usage.core_id, usage.usage_percent, theme)
+                    ctx.hits.add(cell_area, Target::Cpu(usage.core_id));
+                    CompactCpuBar::new(usage.core_id, usage.usage_percent, ctx.theme)
+                        .steal(usage.steal_percent)
+                        .highlight(ctx.labels.contains_key(&usage.core_id))
+                        .selected(usage.core_id == ctx.selected_cpu)
                         .render(cell_area, buf);
                 }
                 y += 1;
@@ -210,14 +255,8 @@ fn render_grouped_compact(
 const HEATMAP_ROW_LABEL_WIDTH: u16 = 5;
 
 /// Heatmap mode: ultra-dense within each group with labels
-fn render_grouped_heatmap(
-    area: Rect,
-    buf: &mut Buffer,
-    groups: &[GroupUsage],
-    scroll_offset: usize,
-    theme: &Theme,
-) {
-    // Reserve space for row labels
+fn render_grouped_heatmap(area: Rect, buf: &mut Buffer, groups: &[GroupUsage], ctx: &ViewContext) {
+    // Reserve space for row ctx.labels
     let content_width = area.width.saturating_sub(HEATMAP_ROW_LABEL_WIDTH);
     let content_x = area.x + HEATMAP_ROW_LABEL_WIDTH;
 
@@ -237,44 +276,59 @@ fn render_grouped_heatmap(
     }
 
     let visible_height = area.height as usize;
-    let max_scroll = total_lines.saturating_sub(visible_height);
-    let scroll = scroll_offset.min(max_scroll);
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, total_lines, visible_height);
 
     let mut current_line = 0;
     let mut y = area.y;
 
     for group in groups {
         // Group header
+        ctx.hits
+            .place(Target::Group(group.id), current_line, 1, visible_height);
         if current_line >= scroll && y < area.y + area.height {
             let header_area = Rect::new(area.x, y, area.width, 1);
-            GroupHeader::new(&group.name, group.usage_percent, group.core_count, theme)
-                .render(header_area, buf);
+            ctx.hits.add(header_area, Target::Group(group.id));
+            GroupHeader::new(
+                &group.name,
+                group.usage_percent,
+                group.core_count,
+                ctx.theme,
+            )
+            .selected(ctx.selected_group == Some(group.id))
+            .render(header_area, buf);
             y += 1;
         }
         current_line += 1;
 
         // Column header for this group
         if current_line >= scroll && y < area.y + area.height {
-            // Show column markers every 16 columns, including the final count
+            // Show column markers every 16 ctx.columns, including the final count
             for col_marker in (0..=layout.cols).step_by(16) {
                 let x = content_x + (col_marker as u16 * layout.cell_width);
                 let label = format!("+{}", col_marker);
                 if x + label.len() as u16 <= area.x + area.width {
-                    buf.set_string(x, y, &label, theme.dim_style());
+                    buf.set_string(x, y, &label, ctx.theme.dim_style());
                 }
             }
             y += 1;
         }
         current_line += 1;
 
-        // Core cells in multi-column with row labels
+        // Core cells in multi-column with row ctx.labels
         let rows_for_cores = (group.member_usages.len() + layout.cols - 1) / layout.cols;
         for row in 0..rows_for_cores {
+            let members = group.member_usages.iter().skip(row * layout.cols).take(layout.cols);
+            for usage in members {
+                ctx.hits
+                    .place(Target::Cpu(usage.core_id), current_line, 1, visible_height);
+            }
             if current_line >= scroll && y < area.y + area.height {
                 // Row label: show first core ID of this row
                 if let Some(first_usage) = group.member_usages.get(row * layout.cols) {
                     let label = format!("{:>4}:", first_usage.core_id);
-                    buf.set_string(area.x, y, &label, theme.dim_style());
+                    buf.set_string(area.x, y, &label, ctx.theme.dim_style());
                 }
 
                 for col in 0..layout.cols {
@@ -285,7 +339,10 @@ fn render_grouped_heatmap(
                     let usage = &group.member_usages[idx];
                     let x = content_x + (col as u16 * layout.cell_width);
                     let cell_area = Rect::new(x, y, layout.cell_width, 1);
-                    HeatmapCell::new(usage.core_id, usage.usage_percent, theme)
+                    ctx.hits.add(cell_area, Target::Cpu(usage.core_id));
+                    HeatmapCell::new(usage.core_id, usage.usage_percent, ctx.theme)
+                        .marked(ctx.labels.contains_key(&usage.core_id))
+                        .selected(usage.core_id == ctx.selected_cpu)
                         .render(cell_area, buf);
                 }
                 y += 1;
@@ -300,3 +357,72 @@ fn render_grouped_heatmap(
         current_line += 1;
     }
 }
+
+/// Rows used by each group's sparkline in sparkline mode
+const GROUP_SPARKLINE_HEIGHT: u16 = 2;
+
+/// Sparkline mode: each group header followed by its usage history
+fn render_grouped_sparkline(
+    area: Rect,
+    buf: &mut Buffer,
+    groups: &[GroupUsage],
+    kind: GroupKind,
+    ctx: &ViewContext,
+) {
+    // Header + sparkline + spacing per group
+    let lines_per_group = 2 + GROUP_SPARKLINE_HEIGHT as usize;
+    let total_lines = groups.len() * lines_per_group;
+
+    let visible_height = area.height as usize;
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, total_lines, visible_height);
+
+    let mut current_line = 0;
+    let mut y = area.y;
+    let bottom = area.y + area.height;
+
+    for group in groups {
+        // The cursor should bring the whole sparkline into view, not just the header
+        ctx.hits.place(
+            Target::Group(group.id),
+            current_line,
+            1 + GROUP_SPARKLINE_HEIGHT as usize,
+            visible_height,
+        );
+
+        // Group header
+        if current_line >= scroll && y < bottom {
+            let header_area = Rect::new(area.x, y, area.width, 1);
+            ctx.hits.add(header_area, Target::Group(group.id));
+            GroupHeader::new(
+                &group.name,
+                group.usage_percent,
+                group.core_count,
+                ctx.theme,
+            )
+            .selected(ctx.selected_group == Some(group.id))
+            .render(header_area, buf);
+            y += 1;
+        }
+        current_line += 1;
+
+        // Sparkline (drawn whole once its first line is reached, clipped at the bottom)
+        if current_line + GROUP_SPARKLINE_HEIGHT as usize > scroll && y < bottom {
+            let skipped = scroll.saturating_sub(current_line) as u16;
+            let height = (GROUP_SPARKLINE_HEIGHT - skipped).min(bottom - y);
+            let series = ctx.history.group_series(kind, group.id);
+            let spark_area = Rect::new(area.x + 1, y, area.width.saturating_sub(2), height);
+            ctx.hits.add(spark_area, Target::Group(group.id));
+            UsageSparkline::new(&series, ctx.theme).render(spark_area, buf);
+            y += height;
+        }
+        current_line += GROUP_SPARKLINE_HEIGHT as usize;
+
+        // Spacing
+        if current_line >= scroll && y < bottom {
+            y += 1;
+        }
+        current_line += 1;
+    }
+}
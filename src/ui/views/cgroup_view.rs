@@ -0,0 +1,106 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::Widget,
+};
+
+use crate::stats::CgroupUsage;
+use crate::topology::{format_cpu_list, ZenTopology};
+use crate::ui::hitmap::HitMap;
+use crate::ui::theme::Theme;
+use crate::ui::widgets::GroupHeader;
+
+/// Lines used per cgroup: header + detail + spacing
+const LINES_PER_CGROUP: usize = 3;
+
+/// Render cgroup v2 usage view
+pub fn render_cgroup_view(
+    area: Rect,
+    buf: &mut Buffer,
+    topology: &ZenTopology,
+    cgroups: &[CgroupUsage],
+    scroll_offset: usize,
+    theme: &Theme,
+    hits: &HitMap,
+) {
+    if area.height == 0 {
+        return;
+    }
+
+    if cgroups.is_empty() {
+        buf.set_string(
+            area.x + 1,
+            area.y,
+            "No cgroup v2 CPU statistics found under /sys/fs/cgroup",
+            theme.dim_style(),
+        );
+        return;
+    }
+
+    // Scroll by whole cgroups so header and detail stay together
+    let visible_count = (area.height as usize + 1) / LINES_PER_CGROUP;
+    let scroll = hits.scroll(scroll_offset, cgroups.len(), visible_count.max(1));
+
+    let mut y = area.y;
+    for cgroup in cgroups.iter().skip(scroll) {
+        if y >= area.y + area.height {
+            break;
+        }
+
+        let header_area = Rect::new(area.x, y, area.width, 1);
+        GroupHeader::new(&cgroup.path, cgroup.usage_percent, cgroup.cpus.len(), theme)
+            .render(header_area, buf);
+        y += 1;
+
+        if y >= area.y + area.height {
+            break;
+        }
+        render_cgroup_detail(
+            Rect::new(area.x, y, area.width, 1),
+            buf,
+            topology,
+            cgroup,
+            theme,
+        );
+        y += LINES_PER_CGROUP as u16 - 1;
+    }
+}
+
+/// Detail line: CPU consumption, CCD/NUMA coverage and throttling
+fn render_cgroup_detail(
+    area: Rect,
+    buf: &mut Buffer,
+    topology: &ZenTopology,
+    cgroup: &CgroupUsage,
+    theme: &Theme,
+) {
+    let detail = format!(
+        "  {:6.2} CPUs | cpus {} | CCD {} | NUMA {} ",
+        cgroup.cpu_count_used,
+        format_cpu_list(&cgroup.cpus),
+        format_cpu_list(&topology.ccds_for_cpus(&cgroup.cpus)),
+        format_cpu_list(&topology.numa_nodes_for_cpus(&cgroup.cpus)),
+    );
+    let (x, _) = buf.set_stringn(
+        area.x,
+        area.y,
+        &detail,
+        area.width as usize,
+        theme.text_style(),
+    );
+
+    if cgroup.is_throttled() {
+        let throttle = format!(
+            " THROTTLED {}x / {:.1}ms ",
+            cgroup.nr_throttled,
+            cgroup.throttled_usec as f32 / 1000.0
+        );
+        let style = Style::default()
+            .fg(theme.header_fg)
+            .bg(theme.bar_critical)
+            .add_modifier(Modifier::BOLD);
+        let remaining = (area.x + area.width).saturating_sub(x) as usize;
+        buf.set_stringn(x, area.y, &throttle, remaining, style);
+    }
+}
@@ -0,0 +1,184 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::Widget,
+};
+
+use crate::remote::HostView;
+use crate::stats::GroupUsage;
+use crate::ui::hitmap::{HitMap, Target};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::HeatmapCell;
+
+/// Maximum width of the host name column
+const MAX_NAME_WIDTH: usize = 24;
+
+/// Width of the aggregate usage bar
+const BAR_WIDTH: usize = 10;
+
+/// Render the multi-host overview: one row per host with its aggregate
+/// usage, CCD spread and a CCD heatmap strip per socket
+pub fn render_cluster_view(
+    area: Rect,
+    buf: &mut Buffer,
+    hosts: &[HostView],
+    selected_host: usize,
+    scroll_offset: usize,
+    theme: &Theme,
+    hits: &HitMap,
+) {
+    if area.height < 2 || hosts.is_empty() {
+        return;
+    }
+
+    let name_width = hosts
+        .iter()
+        .map(|h| h.name.len())
+        .max()
+        .unwrap_or(0)
+        .clamp(4, MAX_NAME_WIDTH);
+
+    // Column header
+    buf.set_string(
+        area.x,
+        area.y,
+        format!(
+            "  {:<name_width$} {:>6} {:<bar_width$} {:>6}  CCDs per socket",
+            "Host",
+            "Usage",
+            "",
+            "Spread",
+            name_width = name_width,
+            bar_width = BAR_WIDTH
+        ),
+        theme.dim_style(),
+    );
+
+    let visible_rows = (area.height - 1) as usize;
+    let scroll = hits.scroll(scroll_offset, hosts.len(), visible_rows);
+
+    for idx in 0..hosts.len() {
+        hits.place(Target::Host(idx), idx, 1, visible_rows);
+    }
+
+    for (i, (idx, host)) in hosts
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(visible_rows)
+        .enumerate()
+    {
+        let y = area.y + 1 + i as u16;
+        hits.add(Rect::new(area.x, y, area.width, 1), Target::Host(idx));
+        render_host_row(area, buf, y, host, idx == selected_host, name_width, theme);
+    }
+}
+
+fn render_host_row(
+    area: Rect,
+    buf: &mut Buffer,
+    y: u16,
+    host: &HostView,
+    selected: bool,
+    name_width: usize,
+    theme: &Theme,
+) {
+    let right = area.x + area.width;
+    let mut x = area.x;
+
+    let name_style = if selected {
+        Style::default()
+            .fg(theme.text_highlight)
+            .add_modifier(Modifier::BOLD)
+    } else if host.connected {
+        theme.text_style()
+    } else {
+        theme.dim_style()
+    };
+    let name: String = host.name.chars().take(name_width).collect();
+    buf.set_string(
+        x,
+        y,
+        format!(
+            "{}{:<width$}",
+            if selected { "> " } else { "  " },
+            name,
+            width = name_width
+        ),
+        name_style,
+    );
+    x += name_width as u16 + 3;
+
+    if !host.connected {
+        buf.set_string(x, y, "disconnected", theme.dim_style());
+        return;
+    }
+
+    // Aggregate usage with a small bar
+    let usage = host.stats.total_usage;
+    buf.set_string(x, y, format!("{:>5.1}%", usage), theme.text_style());
+    x += 7;
+    let filled = ((usage.clamp(0.0, 100.0) / 100.0) * BAR_WIDTH as f32).round() as usize;
+    buf.set_string(
+        x,
+        y,
+        "\u{2588}".repeat(filled),
+        Style::default().fg(theme.usage_color(usage)),
+    );
+    buf.set_string(
+        x + filled as u16,
+        y,
+        "\u{2591}".repeat(BAR_WIDTH - filled),
+        theme.dim_style(),
+    );
+    x += BAR_WIDTH as u16 + 1;
+
+    // CCD averages; the spread between hottest and coldest CCD shows imbalance
+    let ccds: Vec<GroupUsage> = host
+        .topology
+        .ccds
+        .iter()
+        .map(|ccd| {
+            let usages: Vec<_> = ccd
+                .cores
+                .iter()
+                .filter_map(|&cpu_id| host.stats.usage(cpu_id).cloned())
+                .collect();
+            GroupUsage::from_cores(format!("CCD {}", ccd.id), ccd.id, &usages)
+        })
+        .collect();
+    let spread = ccds
+        .iter()
+        .map(|c| c.usage_percent)
+        .reduce(f32::max)
+        .zip(ccds.iter().map(|c| c.usage_percent).reduce(f32::min))
+        .map_or(0.0, |(max, min)| max - min);
+    buf.set_string(
+        x,
+        y,
+        format!("{:>5.1}%", spread),
+        Style::default().fg(theme.usage_color(spread)),
+    );
+    x += 8;
+
+    // Heatmap strip per socket
+    for socket in 0..host.topology.packages.max(1) {
+        if x + 3 >= right {
+            return;
+        }
+        buf.set_string(x, y, format!("S{}", socket), theme.dim_style());
+        x += 3;
+        for (ccd, usage) in host.topology.ccds.iter().zip(&ccds) {
+            if ccd.package_id != socket {
+                continue;
+            }
+            if x + 2 > right {
+                return;
+            }
+            HeatmapCell::new(ccd.id, usage.usage_percent, theme).render(Rect::new(x, y, 2, 1), buf);
+            x += 2;
+        }
+        x += 1;
+    }
+}
@@ -1,46 +1,58 @@
-use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use ratatui::{buffer::Buffer, layout::Rect, style::Modifier, widgets::Widget};
 
 use crate::app::DisplayMode;
-use crate::stats::SystemStats;
 use crate::topology::{CpuCore, ZenTopology};
+use crate::ui::hitmap::Target;
 use crate::ui::layout::GridLayout;
 use crate::ui::theme::Theme;
-use crate::ui::widgets::{CompactCpuBar, CpuBar, HeatmapCell};
+use crate::ui::views::waterfall::{render_waterfall, WaterfallRow};
+use crate::ui::views::ViewContext;
+use crate::ui::widgets::{CompactCpuBar, CpuBar, HeatmapCell, UsageSparkline};
 
 /// Render individual core view
-pub fn render_core_view(
-    area: Rect,
-    buf: &mut Buffer,
-    topology: &ZenTopology,
-    stats: &SystemStats,
-    show_smt: bool,
-    scroll_offset: usize,
-    display_mode: DisplayMode,
-    theme: &Theme,
-    socket_filter: Option<usize>,
-) {
+pub fn render_core_view(area: Rect, buf: &mut Buffer, ctx: &ViewContext) {
     if area.height == 0 {
         return;
     }
 
-    // Filter cores based on SMT setting and socket filter
-    let cores_to_show: Vec<_> = filter_cores(topology, show_smt, socket_filter);
+    // Filter cores based on SMT setting, socket filter and CPU list
+    let cores_to_show: Vec<_> =
+        filter_cores(ctx.topology, ctx.show_smt, ctx.socket_filter, ctx.cpus);
 
-    match display_mode {
+    match ctx.display_mode {
         DisplayMode::Full => {
-            render_full_view(area, buf, &cores_to_show, stats, scroll_offset, theme);
+            render_full_view(area, buf, &cores_to_show, ctx);
         }
         DisplayMode::Compact => {
-            render_compact_view(area, buf, &cores_to_show, stats, scroll_offset, theme);
+            render_compact_view(area, buf, &cores_to_show, ctx);
         }
         DisplayMode::Heatmap => {
-            render_heatmap_view(area, buf, &cores_to_show, stats, scroll_offset, theme);
+            render_heatmap_view(area, buf, &cores_to_show, ctx);
+        }
+        DisplayMode::Sparkline => {
+            render_sparkline_view(area, buf, &cores_to_show, ctx);
+        }
+        DisplayMode::Waterfall => {
+            let rows: Vec<WaterfallRow> = cores_to_show
+                .iter()
+                .map(|core| WaterfallRow {
+                    label: format!("CPU{:2}", core.id),
+                    target: Target::Cpu(core.id),
+                    series: ctx.history.cpu_series(core.id),
+                })
+                .collect();
+            render_waterfall(area, buf, &rows, Target::Cpu(ctx.selected_cpu), ctx);
         }
     }
 }
 
-/// Filter cores based on SMT setting and socket filter
-pub fn filter_cores(topology: &ZenTopology, show_smt: bool, socket_filter: Option<usize>) -> Vec<&CpuCore> {
+/// Filter cores based on SMT setting, socket filter and CPU list
+pub fn filter_cores<'a>(
+    topology: &'a ZenTopology,
+    show_smt: bool,
+    socket_filter: Option<usize>,
+    cpus: Option<&[usize]>,
+) -> Vec<&'a CpuCore> {
     topology
         .cores
         .iter()
@@ -51,6 +63,12 @@ pub fn filter_cores(topology: &ZenTopology, show_smt: bool, socket_filter: Optio
                     return false;
                 }
             }
+            // Filter by CPU list if specified
+            if let Some(cpus) = cpus {
+                if !cpus.contains(&c.id) {
+                    return false;
+                }
+            }
             // Filter SMT siblings if not showing SMT
             if !show_smt {
                 if let Some(sibling) = c.smt_sibling {
@@ -65,48 +83,52 @@ pub fn filter_cores(topology: &ZenTopology, show_smt: bool, socket_filter: Optio
 }
 
 /// Render full view (original 1-core-per-line)
-fn render_full_view(
-    area: Rect,
-    buf: &mut Buffer,
-    cores: &[&CpuCore],
-    stats: &SystemStats,
-    scroll_offset: usize,
-    theme: &Theme,
-) {
+fn render_full_view(area: Rect, buf: &mut Buffer, cores: &[&CpuCore], ctx: &ViewContext) {
     let visible_count = area.height as usize;
     let total_cores = cores.len();
 
-    let max_scroll = total_cores.saturating_sub(visible_count);
-    let scroll = scroll_offset.min(max_scroll);
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, total_cores, visible_count);
+
+    for (line, core) in cores.iter().enumerate() {
+        ctx.hits.place(Target::Cpu(core.id), line, 1, visible_count);
+    }
 
     for (i, core) in cores.iter().skip(scroll).take(visible_count).enumerate() {
-        let usage = stats
-            .core_usages
-            .get(core.id)
-            .map(|u| u.usage_percent)
-            .unwrap_or(0.0);
+        let (usage, steal, guest, frequency) = ctx
+            .stats
+            .usage(core.id)
+            .map(|u| (u.usage_percent, u.steal_percent, u.guest_percent, u.frequency_mhz))
+            .unwrap_or((0.0, 0.0, 0.0, None));
 
         let label = format!("CPU{:2}", core.id);
         let bar_area = Rect::new(area.x, area.y + i as u16, area.width, 1);
-
-        CpuBar::new(&label, usage, theme).render(bar_area, buf);
+        ctx.hits.add(bar_area, Target::Cpu(core.id));
+
+        CpuBar::new(&label, usage, ctx.theme)
+            .columns(ctx.columns)
+            .virt(steal, guest)
+            .frequency(frequency)
+            .selected(core.id == ctx.selected_cpu)
+            .annotation(ctx.labels.get(&core.id).map(String::as_str))
+            .render(bar_area, buf);
     }
 }
 
 /// Render compact view (16 cores per row)
-fn render_compact_view(
-    area: Rect,
-    buf: &mut Buffer,
-    cores: &[&CpuCore],
-    stats: &SystemStats,
-    scroll_offset: usize,
-    theme: &Theme,
-) {
+fn render_compact_view(area: Rect, buf: &mut Buffer, cores: &[&CpuCore], ctx: &ViewContext) {
     let layout = GridLayout::compact(area, cores.len());
     let visible_rows = area.height as usize;
 
-    let max_scroll = layout.rows.saturating_sub(visible_rows);
-    let scroll = scroll_offset.min(max_scroll);
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, layout.rows, visible_rows);
+
+    for (i, core) in cores.iter().enumerate() {
+        ctx.hits
+            .place(Target::Cpu(core.id), i / layout.cols, 1, visible_rows);
+    }
 
     for (i, core) in cores.iter().enumerate() {
         let row = i / layout.cols;
@@ -127,14 +149,19 @@ fn render_compact_view(
         let y = area.y + display_row as u16;
 
         let cell_area = Rect::new(x, y, layout.cell_width, 1);
-
-        let usage = stats
-            .core_usages
-            .get(core.id)
-            .map(|u| u.usage_percent)
-            .unwrap_or(0.0);
-
-        CompactCpuBar::new(core.id, usage, theme).render(cell_area, buf);
+        ctx.hits.add(cell_area, Target::Cpu(core.id));
+
+        let (usage, steal) = ctx
+            .stats
+            .usage(core.id)
+            .map(|u| (u.usage_percent, u.steal_percent))
+            .unwrap_or((0.0, 0.0));
+
+        CompactCpuBar::new(core.id, usage, ctx.theme)
+            .steal(steal)
+            .highlight(ctx.labels.contains_key(&core.id))
+            .selected(core.id == ctx.selected_cpu)
+            .render(cell_area, buf);
     }
 }
 
@@ -142,19 +169,12 @@ fn render_compact_view(
 const HEATMAP_ROW_LABEL_WIDTH: u16 = 5;
 
 /// Render heatmap view (96 cores per row with labels)
-fn render_heatmap_view(
-    area: Rect,
-    buf: &mut Buffer,
-    cores: &[&CpuCore],
-    stats: &SystemStats,
-    scroll_offset: usize,
-    theme: &Theme,
-) {
+fn render_heatmap_view(area: Rect, buf: &mut Buffer, cores: &[&CpuCore], ctx: &ViewContext) {
     if area.height < 2 || area.width < HEATMAP_ROW_LABEL_WIDTH + 16 {
         return;
     }
 
-    // Reserve space for row labels and column header
+    // Reserve space for row ctx.labels and column header
     let content_width = area.width.saturating_sub(HEATMAP_ROW_LABEL_WIDTH);
     let content_area = Rect::new(
         area.x + HEATMAP_ROW_LABEL_WIDTH,
@@ -166,13 +186,19 @@ fn render_heatmap_view(
     let layout = GridLayout::heatmap(content_area, cores.len());
     let visible_rows = content_area.height as usize;
 
-    let max_scroll = layout.rows.saturating_sub(visible_rows);
-    let scroll = scroll_offset.min(max_scroll);
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, layout.rows, visible_rows);
 
     // Render column header
-    render_heatmap_column_header(area, buf, layout.cols, layout.cell_width, theme);
+    render_heatmap_column_header(area, buf, layout.cols, layout.cell_width, ctx.theme);
 
-    // Render cores with row labels
+    for (i, core) in cores.iter().enumerate() {
+        ctx.hits
+            .place(Target::Cpu(core.id), i / layout.cols, 1, visible_rows);
+    }
+
+    // Render cores with row ctx.labels
     for (i, core) in cores.iter().enumerate() {
         let row = i / layout.cols;
 
@@ -192,21 +218,77 @@ fn render_heatmap_view(
             let first_core_id = cores.get(row * layout.cols).map(|c| c.id).unwrap_or(0);
             let label = format!("{:>4}:", first_core_id);
             let y = content_area.y + display_row as u16;
-            buf.set_string(area.x, y, &label, theme.dim_style());
+            buf.set_string(area.x, y, &label, ctx.theme.dim_style());
         }
 
         let x = content_area.x + (col as u16 * layout.cell_width);
         let y = content_area.y + display_row as u16;
 
         let cell_area = Rect::new(x, y, layout.cell_width, 1);
+        ctx.hits.add(cell_area, Target::Cpu(core.id));
 
-        let usage = stats
-            .core_usages
-            .get(core.id)
+        let usage = ctx
+            .stats
+            .usage(core.id)
+            .map(|u| u.usage_percent)
+            .unwrap_or(0.0);
+
+        HeatmapCell::new(core.id, usage, ctx.theme)
+            .marked(ctx.labels.contains_key(&core.id))
+            .selected(core.id == ctx.selected_cpu)
+            .render(cell_area, buf);
+    }
+}
+
+/// Width of the "CPU XX " label in sparkline mode
+const SPARKLINE_LABEL_WIDTH: u16 = 8;
+
+/// Render sparkline view (usage history per core)
+fn render_sparkline_view(area: Rect, buf: &mut Buffer, cores: &[&CpuCore], ctx: &ViewContext) {
+    // Label + sparkline + " XX.X%"
+    if area.width < SPARKLINE_LABEL_WIDTH + 8 {
+        return;
+    }
+
+    let visible_count = area.height as usize;
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, cores.len(), visible_count);
+    let spark_width = area.width - SPARKLINE_LABEL_WIDTH - 7;
+
+    for (line, core) in cores.iter().enumerate() {
+        ctx.hits.place(Target::Cpu(core.id), line, 1, visible_count);
+    }
+
+    for (i, core) in cores.iter().skip(scroll).take(visible_count).enumerate() {
+        let y = area.y + i as u16;
+        ctx.hits
+            .add(Rect::new(area.x, y, area.width, 1), Target::Cpu(core.id));
+        let usage = ctx
+            .stats
+            .usage(core.id)
             .map(|u| u.usage_percent)
             .unwrap_or(0.0);
 
-        HeatmapCell::new(core.id, usage, theme).render(cell_area, buf);
+        let label_style = if core.id == ctx.selected_cpu {
+            ctx.theme
+                .text_style()
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            ctx.theme.text_style()
+        };
+        buf.set_string(area.x, y, format!("{:>7} ", format!("CPU{:2}", core.id)), label_style);
+
+        let series = ctx.history.cpu_series(core.id);
+        let spark_area = Rect::new(area.x + SPARKLINE_LABEL_WIDTH, y, spark_width, 1);
+        UsageSparkline::new(&series, ctx.theme).render(spark_area, buf);
+
+        buf.set_string(
+            spark_area.x + spark_width,
+            y,
+            format!(" {:5.1}%", usage),
+            ctx.theme.text_style(),
+        );
     }
 }
 
@@ -0,0 +1,257 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Widget},
+};
+
+use crate::irq::InterruptStats;
+use crate::topology::format_cpu_list;
+use crate::ui::hitmap::Target;
+use crate::ui::views::core_view::filter_cores;
+use crate::ui::views::ViewContext;
+
+/// Render per-CPU/per-CCD interrupt distribution with IRQ drill-down
+pub fn render_irq_view(
+    area: Rect,
+    buf: &mut Buffer,
+    interrupts: &InterruptStats,
+    ctx: &ViewContext,
+) {
+    if area.height == 0 {
+        return;
+    }
+
+    let [list, detail] = split(area);
+    render_rate_list(list, buf, interrupts, ctx);
+    render_irq_detail(detail, buf, interrupts, ctx);
+}
+
+/// Part of the view taken by the scrolling rate list, directly above the
+/// detail's top border
+pub fn rate_list_area(area: Rect) -> Rect {
+    split(area)[0]
+}
+
+/// Rate list above, IRQ detail below
+fn split(area: Rect) -> [Rect; 2] {
+    Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(area)
+}
+
+/// Per-CCD headers followed by one rate bar per CPU
+fn render_rate_list(area: Rect, buf: &mut Buffer, interrupts: &InterruptStats, ctx: &ViewContext) {
+    let theme = ctx.theme;
+
+    // Build the flat line list: (ccd header | cpu row), honouring the SMT
+    // setting, --socket and --cpus like the other per-CPU views
+    enum Row {
+        Ccd(usize, Vec<usize>),
+        Cpu(usize),
+    }
+    let shown: Vec<usize> = filter_cores(ctx.topology, ctx.show_smt, ctx.socket_filter, ctx.cpus)
+        .iter()
+        .map(|c| c.id)
+        .collect();
+    let mut rows = Vec::new();
+    for ccd in &ctx.topology.ccds {
+        let cpus: Vec<usize> = ccd
+            .cores
+            .iter()
+            .copied()
+            .filter(|cpu_id| shown.contains(cpu_id))
+            .collect();
+        if cpus.is_empty() {
+            continue;
+        }
+        rows.push(Row::Ccd(ccd.id, cpus.clone()));
+        rows.extend(cpus.into_iter().map(Row::Cpu));
+    }
+
+    // The list scrolls freely; moving the selection scrolls it into view
+    let visible_height = area.height as usize;
+    let scroll = ctx
+        .hits
+        .scroll(ctx.scroll_offset, rows.len(), visible_height);
+    for (line, row) in rows.iter().enumerate() {
+        if let Row::Cpu(cpu_id) = row {
+            ctx.hits
+                .place(Target::Cpu(*cpu_id), line, 1, visible_height);
+        }
+    }
+
+    let max_rate = shown
+        .iter()
+        .map(|&cpu_id| {
+            let (hard, soft) = interrupts.cpu_rate(cpu_id);
+            hard + soft
+        })
+        .fold(1.0_f32, f32::max);
+
+    for (i, row) in rows.iter().skip(scroll).take(visible_height).enumerate() {
+        let y = area.y + i as u16;
+        match row {
+            Row::Ccd(ccd_id, cpus) => {
+                let (hard, soft) = cpus.iter().fold((0.0, 0.0), |(h, s), &cpu_id| {
+                    let (hard, soft) = interrupts.cpu_rate(cpu_id);
+                    (h + hard, s + soft)
+                });
+                let header = format!(
+                    " CCD {} ({} cpus) - hard {}/s soft {}/s ",
+                    ccd_id,
+                    cpus.len(),
+                    format_rate(hard),
+                    format_rate(soft)
+                );
+                let style = Style::default()
+                    .fg(theme.header_fg)
+                    .bg(theme.usage_color((hard + soft) / (max_rate * cpus.len() as f32) * 100.0));
+                buf.set_stringn(area.x, y, &header, area.width as usize, style);
+                let remaining = area.width.saturating_sub(header.len() as u16);
+                if remaining > 0 {
+                    buf.set_string(
+                        area.x + header.len() as u16,
+                        y,
+                        " ".repeat(remaining as usize),
+                        style,
+                    );
+                }
+            }
+            Row::Cpu(cpu_id) => {
+                let row_area = Rect::new(area.x, y, area.width, 1);
+                render_rate_bar(row_area, buf, interrupts, *cpu_id, max_rate, ctx);
+                ctx.hits.add(row_area, Target::Cpu(*cpu_id));
+            }
+        }
+    }
+}
+
+/// "CPU 12 [|||||     ] hard  12.3k soft   4.1k"
+fn render_rate_bar(
+    area: Rect,
+    buf: &mut Buffer,
+    interrupts: &InterruptStats,
+    cpu_id: usize,
+    max_rate: f32,
+    ctx: &ViewContext,
+) {
+    const LABEL_WIDTH: u16 = 9;
+    const RATES_WIDTH: u16 = 25;
+
+    let theme = ctx.theme;
+    let (hard, soft) = interrupts.cpu_rate(cpu_id);
+    let bar_width = area.width.saturating_sub(LABEL_WIDTH + RATES_WIDTH + 2) as usize;
+    let label_style = if cpu_id == ctx.selected_cpu {
+        theme
+            .text_style()
+            .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        theme.text_style()
+    };
+    buf.set_string(area.x, area.y, format!("  CPU{:>3} ", cpu_id), label_style);
+
+    let x = area.x + LABEL_WIDTH;
+    if bar_width >= 2 {
+        let percent = (hard + soft) / max_rate * 100.0;
+        let filled = ((percent / 100.0) * bar_width as f32).round() as usize;
+        buf.set_string(x, area.y, "[", theme.dim_style());
+        buf.set_string(
+            x + 1,
+            area.y,
+            "|".repeat(filled.min(bar_width)),
+            theme.bar_style(percent),
+        );
+        buf.set_string(x + 1 + bar_width as u16, area.y, "]", theme.dim_style());
+    }
+
+    let rates = format!(
+        " hard {:>7} soft {:>7}",
+        format_rate(hard),
+        format_rate(soft)
+    );
+    let rates_x = x + if bar_width >= 2 {
+        bar_width as u16 + 2
+    } else {
+        0
+    };
+    let remaining = (area.x + area.width).saturating_sub(rates_x) as usize;
+    buf.set_stringn(rates_x, area.y, &rates, remaining, theme.text_style());
+}
+
+/// IRQ lines landing on the selected CPU, with affinity and device locality
+fn render_irq_detail(area: Rect, buf: &mut Buffer, interrupts: &InterruptStats, ctx: &ViewContext) {
+    let theme = ctx.theme;
+    let selected_cpu = ctx.selected_cpu;
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .title(format!(" IRQs on CPU {} ([←/→] select) ", selected_cpu))
+        .border_style(Style::default().fg(theme.border))
+        .title_style(
+            Style::default()
+                .fg(theme.text_highlight)
+                .add_modifier(Modifier::BOLD),
+        );
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if inner.height == 0 {
+        return;
+    }
+
+    let cpu_numa = ctx.topology.core(selected_cpu).map(|c| c.numa_node);
+    let lines = interrupts.lines_on(selected_cpu);
+    if lines.is_empty() {
+        buf.set_string(
+            inner.x,
+            inner.y,
+            "No interrupts on this CPU",
+            theme.dim_style(),
+        );
+        return;
+    }
+
+    let remote_style = Style::default().fg(theme.bar_critical);
+
+    for (i, line) in lines.iter().take(inner.height as usize).enumerate() {
+        let y = inner.y + i as u16;
+        let device = line.device.as_ref();
+        let device_numa = device.and_then(|d| d.numa_node);
+
+        let text = format!(
+            "{:>6} {:>8}/s  {:<20} aff {:<12} {}",
+            line.irq,
+            format_rate(line.rate_on(selected_cpu)),
+            line.action(),
+            if line.affinity.is_empty() {
+                String::from("-")
+            } else {
+                format_cpu_list(&line.affinity)
+            },
+            device.map_or(String::new(), |d| {
+                format!(
+                    "{} {} NUMA {}",
+                    d.address,
+                    d.driver.as_deref().unwrap_or("-"),
+                    d.numa_node.map_or(String::from("?"), |n| n.to_string())
+                )
+            }),
+        );
+
+        // Device interrupts serviced off their local NUMA node are highlighted
+        let style = match (device_numa, cpu_numa) {
+            (Some(dev), Some(cpu)) if dev != cpu => remote_style,
+            _ => theme.text_style(),
+        };
+        buf.set_stringn(inner.x, y, &text, inner.width as usize, style);
+    }
+}
+
+/// Format an event rate compactly (e.g. "950", "12.3k", "1.2M")
+fn format_rate(rate: f32) -> String {
+    if rate >= 1_000_000.0 {
+        format!("{:.1}M", rate / 1_000_000.0)
+    } else if rate >= 1_000.0 {
+        format!("{:.1}k", rate / 1_000.0)
+    } else {
+        format!("{:.0}", rate)
+    }
+}
@@ -1,7 +1,44 @@
 pub mod ccd_view;
+pub mod cgroup_view;
+pub mod cluster_view;
 pub mod core_view;
+pub mod irq_view;
 pub mod nps_view;
+pub mod waterfall;
+
+use std::collections::HashMap;
+
+use crate::app::DisplayMode;
+use crate::config::Columns;
+use crate::stats::{History, SystemStats};
+use crate::topology::ZenTopology;
+use crate::ui::hitmap::HitMap;
+use crate::ui::theme::Theme;
 
 pub use ccd_view::render_ccd_view;
+pub use cgroup_view::render_cgroup_view;
+pub use cluster_view::render_cluster_view;
 pub use core_view::render_core_view;
+pub use irq_view::render_irq_view;
 pub use nps_view::render_nps_view;
+
+/// State shared by the per-CPU views when drawing one pane
+pub struct ViewContext<'a> {
+    pub topology: &'a ZenTopology,
+    pub stats: &'a SystemStats,
+    pub history: &'a History,
+    /// Guest vCPU labels keyed by host CPU
+    pub labels: &'a HashMap<usize, String>,
+    pub show_smt: bool,
+    pub scroll_offset: usize,
+    pub selected_group: Option<usize>,
+    pub selected_cpu: usize,
+    pub display_mode: DisplayMode,
+    pub columns: Columns,
+    pub theme: &'a Theme,
+    /// Socket whose CPUs this pane shows, if any
+    pub socket_filter: Option<usize>,
+    /// CPUs selected with --cpus
+    pub cpus: Option<&'a [usize]>,
+    pub hits: &'a HitMap,
+}
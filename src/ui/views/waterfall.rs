@@ -0,0 +1,121 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+};
+
+use crate::stats::History;
+use crate::ui::hitmap::Target;
+use crate::ui::views::ViewContext;
+
+/// Maximum width of a waterfall row label
+const MAX_LABEL_WIDTH: usize = 12;
+
+/// A labeled usage series drawn as one waterfall row
+pub struct WaterfallRow {
+    pub label: String,
+    /// What clicking the row selects
+    pub target: Target,
+    /// Usage per sample, oldest first
+    pub series: Vec<f32>,
+}
+
+impl WaterfallRow {
+    /// Average usage of several CPUs at each history sample
+    pub fn from_cpus(label: String, target: Target, cpu_ids: &[usize], history: &History) -> Self {
+        let series = history
+            .samples()
+            .map(|sample| {
+                let values: Vec<f32> = cpu_ids
+                    .iter()
+                    .filter_map(|&id| sample.cpus.get(id).copied())
+                    .collect();
+                if values.is_empty() {
+                    0.0
+                } else {
+                    values.iter().sum::<f32>() / values.len() as f32
+                }
+            })
+            .collect();
+        Self {
+            label,
+            target,
+            series,
+        }
+    }
+}
+
+/// Render a CPU x time waterfall: one row per series, one column per sample,
+/// newest sample at the right edge
+pub fn render_waterfall(
+    area: Rect,
+    buf: &mut Buffer,
+    rows: &[WaterfallRow],
+    selected: Target,
+    ctx: &ViewContext,
+) {
+    if area.height < 2 || rows.is_empty() {
+        return;
+    }
+
+    let label_width = rows
+        .iter()
+        .map(|r| r.label.len())
+        .max()
+        .unwrap_or(0)
+        .min(MAX_LABEL_WIDTH);
+    let content_x = area.x + label_width as u16 + 1;
+    let content_width = (area.x + area.width).saturating_sub(content_x) as usize;
+    if content_width == 0 {
+        return;
+    }
+
+    // Time axis header
+    let offsets = ctx.history.time_offsets();
+    let visible_offsets = &offsets[offsets.len().saturating_sub(content_width)..];
+    if let Some(oldest) = visible_offsets.first() {
+        let axis_x = content_x + (content_width - visible_offsets.len()) as u16;
+        buf.set_string(
+            axis_x,
+            area.y,
+            format!("{:.0}s", oldest),
+            ctx.theme.dim_style(),
+        );
+    }
+    let now_x = (content_x + content_width as u16).saturating_sub(3);
+    buf.set_string(now_x, area.y, "now", ctx.theme.dim_style());
+
+    let visible_rows = (area.height - 1) as usize;
+    let scroll = ctx.hits.scroll(ctx.scroll_offset, rows.len(), visible_rows);
+
+    for (line, row) in rows.iter().enumerate() {
+        ctx.hits.place(row.target, line, 1, visible_rows);
+    }
+
+    for (i, row) in rows.iter().skip(scroll).take(visible_rows).enumerate() {
+        let y = area.y + 1 + i as u16;
+        ctx.hits
+            .add(Rect::new(area.x, y, area.width, 1), row.target);
+        let label: String = row.label.chars().take(label_width).collect();
+        let label_style = if row.target == selected {
+            ctx.theme
+                .text_style()
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            ctx.theme.dim_style()
+        };
+        buf.set_string(
+            area.x,
+            y,
+            format!("{:>width$}", label, width = label_width),
+            label_style,
+        );
+
+        let visible = &row.series[row.series.len().saturating_sub(content_width)..];
+        let x_start = content_x + (content_width - visible.len()) as u16;
+        for (col, &usage) in visible.iter().enumerate() {
+            let style = Style::default().bg(ctx.theme.heat_color(usage));
+            buf.set_string(x_start + col as u16, y, " ", style);
+        }
+    }
+}
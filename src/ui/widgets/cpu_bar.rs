@@ -1,10 +1,11 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Style,
+    style::{Modifier, Style},
     widgets::Widget,
 };
 
+use crate::config::Columns;
 use crate::ui::theme::Theme;
 
 /// CPU usage bar widget (htop-style)
@@ -12,7 +13,11 @@ pub struct CpuBar<'a> {
     label: &'a str,
     usage: f32,
     theme: &'a Theme,
-    show_percentage: bool,
+    columns: Columns,
+    annotation: Option<&'a str>,
+    virt: Option<(f32, f32)>,
+    frequency: Option<u64>,
+    selected: bool,
 }
 
 impl<'a> CpuBar<'a> {
@@ -21,12 +26,46 @@ impl<'a> CpuBar<'a> {
             label,
             usage,
             theme,
-            show_percentage: true,
+            columns: Columns::default(),
+            annotation: None,
+            virt: None,
+            frequency: None,
+            selected: false,
         }
     }
 
     pub fn show_percentage(mut self, show: bool) -> Self {
-        self.show_percentage = show;
+        self.columns.percent = show;
+        self
+    }
+
+    /// Choose which columns follow the bar
+    pub fn columns(mut self, columns: Columns) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Current frequency, shown if the frequency column is enabled
+    pub fn frequency(mut self, mhz: Option<u64>) -> Self {
+        self.frequency = mhz;
+        self
+    }
+
+    /// Text drawn right-aligned inside the bar (e.g. guest vCPU labels)
+    pub fn annotation(mut self, annotation: Option<&'a str>) -> Self {
+        self.annotation = annotation;
+        self
+    }
+
+    /// Steal and guest percentages: adds a steal segment and st/gu columns
+    pub fn virt(mut self, steal: f32, guest: f32) -> Self {
+        self.virt = Some((steal, guest));
+        self
+    }
+
+    /// Highlight the label as the current selection
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
         self
     }
 }
@@ -39,11 +78,17 @@ impl Widget for CpuBar<'_> {
 
         // Format: "CPU 0  [||||||||          ] 65.2%"
         let label_width = 7; // "CPU XX "
-        let percent_width = if self.show_percentage { 7 } else { 0 }; // " XX.X%"
+        let percent_width = if self.columns.percent { 7 } else { 0 }; // " XX.X%"
+        let virt_width = if self.columns.virt && self.virt.is_some() && area.width >= 40 {
+            16 // " st XX.X gu XX.X"
+        } else {
+            0
+        };
+        let freq_width = if self.columns.frequency && area.width >= 40 { 8 } else { 0 }; // " XXXXMHz"
         let bracket_width = 2; // "[]"
-        let bar_width = area
-            .width
-            .saturating_sub(label_width + percent_width + bracket_width) as usize;
+        let bar_width = area.width.saturating_sub(
+            label_width + percent_width + virt_width + freq_width + bracket_width,
+        ) as usize;
 
         if bar_width < 2 {
             return;
@@ -54,7 +99,14 @@ impl Widget for CpuBar<'_> {
 
         // Render label
         let label_str = format!("{:>6} ", self.label);
-        buf.set_string(x, y, &label_str, self.theme.text_style());
+        let label_style = if self.selected {
+            self.theme
+                .text_style()
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            self.theme.text_style()
+        };
+        buf.set_string(x, y, &label_str, label_style);
 
         // Render opening bracket
         let bar_start = x + label_width;
@@ -68,20 +120,61 @@ impl Widget for CpuBar<'_> {
         let bar_color = self.theme.usage_color(self.usage);
         let bar_style = Style::default().fg(bar_color);
 
+        // Steal time follows the usage segment (sysinfo usage excludes it)
+        let steal = self.virt.map_or(0.0, |(steal, _)| steal);
+        let steal_filled = ((steal / 100.0) * bar_width as f32).round() as usize;
+        let steal_filled = steal_filled.min(bar_width - filled);
+        let steal_style = Style::default().fg(self.theme.bar_steal);
+
         for i in 0..bar_width {
-            let char = if i < filled { '|' } else { ' ' };
-            buf.set_string(bar_start + 1 + i as u16, y, char.to_string(), bar_style);
+            let (char, style) = if i < filled {
+                ('|', bar_style)
+            } else if i < filled + steal_filled {
+                ('|', steal_style)
+            } else {
+                (' ', bar_style)
+            };
+            buf.set_string(bar_start + 1 + i as u16, y, char.to_string(), style);
+        }
+
+        // Render annotation over the right end of the bar
+        if let Some(annotation) = self.annotation {
+            let text: String = annotation.chars().take(bar_width).collect();
+            let text_x = bar_start + 1 + (bar_width - text.chars().count()) as u16;
+            let style = Style::default()
+                .fg(self.theme.text_highlight)
+                .add_modifier(Modifier::BOLD);
+            buf.set_string(text_x, y, &text, style);
         }
 
         // Render closing bracket
         buf.set_string(bar_start + 1 + bar_width as u16, y, "]", self.theme.dim_style());
 
         // Render percentage
-        if self.show_percentage {
+        if self.columns.percent {
             let percent_str = format!("{:5.1}%", self.usage);
             let percent_x = bar_start + 2 + bar_width as u16;
             buf.set_string(percent_x, y, &percent_str, self.theme.text_style());
         }
+
+        // Render steal/guest columns
+        if let Some((steal, guest)) = self.virt.filter(|_| virt_width > 0) {
+            let virt_x = bar_start + 2 + bar_width as u16 + percent_width;
+            buf.set_string(virt_x, y, " st", self.theme.dim_style());
+            buf.set_string(virt_x + 3, y, format!("{:5.1}", steal), steal_style);
+            buf.set_string(virt_x + 8, y, " gu", self.theme.dim_style());
+            buf.set_string(virt_x + 11, y, format!("{:5.1}", guest), self.theme.text_style());
+        }
+
+        // Render frequency column
+        if freq_width > 0 {
+            let freq_x = bar_start + 2 + bar_width as u16 + percent_width + virt_width;
+            let freq_str = match self.frequency {
+                Some(mhz) => format!(" {:>4}MHz", mhz),
+                None => String::from("    -   "),
+            };
+            buf.set_string(freq_x, y, &freq_str, self.theme.dim_style());
+        }
     }
 }
 
@@ -90,6 +183,9 @@ pub struct CompactCpuBar<'a> {
     core_id: usize,
     usage: f32,
     theme: &'a Theme,
+    highlight: bool,
+    selected: bool,
+    steal: f32,
 }
 
 impl<'a> CompactCpuBar<'a> {
@@ -98,8 +194,29 @@ impl<'a> CompactCpuBar<'a> {
             core_id,
             usage,
             theme,
+            highlight: false,
+            selected: false,
+            steal: 0.0,
         }
     }
+
+    /// Emphasize the core ID label
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Mark the cell as the current selection
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Steal percentage, drawn as a segment after usage
+    pub fn steal(mut self, steal: f32) -> Self {
+        self.steal = steal;
+        self
+    }
 }
 
 impl Widget for CompactCpuBar<'_> {
@@ -119,7 +236,25 @@ impl Widget for CompactCpuBar<'_> {
 
         // Render core ID (right-aligned, 3 chars)
         let label = format!("{:>3}", self.core_id);
-        buf.set_string(x, y, &label, self.theme.text_style());
+        let label_style = match (self.highlight, self.selected) {
+            (true, selected) => {
+                let style = Style::default()
+                    .fg(self.theme.text_highlight)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED);
+                // Already reversed, so the selection is underlined instead
+                if selected {
+                    style.add_modifier(Modifier::UNDERLINED)
+                } else {
+                    style
+                }
+            }
+            (false, true) => self
+                .theme
+                .text_style()
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            (false, false) => self.theme.text_style(),
+        };
+        buf.set_string(x, y, &label, label_style);
 
         // Render bar [===] (5 chars)
         let bar_width = 3;
@@ -129,10 +264,20 @@ impl Widget for CompactCpuBar<'_> {
         let bar_color = self.theme.usage_color(self.usage);
         let bar_style = Style::default().fg(bar_color);
 
+        let steal_filled = ((self.steal / 100.0) * bar_width as f32).round() as usize;
+        let steal_filled = steal_filled.min(bar_width - filled);
+        let steal_style = Style::default().fg(self.theme.bar_steal);
+
         buf.set_string(x + 3, y, "[", self.theme.dim_style());
         for i in 0..bar_width {
-            let char = if i < filled { '|' } else { ' ' };
-            buf.set_string(x + 4 + i as u16, y, char.to_string(), bar_style);
+            let (char, style) = if i < filled {
+                ('|', bar_style)
+            } else if i < filled + steal_filled {
+                ('|', steal_style)
+            } else {
+                (' ', bar_style)
+            };
+            buf.set_string(x + 4 + i as u16, y, char.to_string(), style);
         }
         buf.set_string(x + 7, y, "]", self.theme.dim_style());
 
@@ -150,6 +295,8 @@ pub struct HeatmapCell<'a> {
     core_id: usize,
     usage: f32,
     theme: &'a Theme,
+    marked: bool,
+    selected: bool,
 }
 
 impl<'a> HeatmapCell<'a> {
@@ -158,8 +305,22 @@ impl<'a> HeatmapCell<'a> {
             core_id,
             usage,
             theme,
+            marked: false,
+            selected: false,
         }
     }
+
+    /// Draw a marker in the separator column
+    pub fn marked(mut self, marked: bool) -> Self {
+        self.marked = marked;
+        self
+    }
+
+    /// Point at the cell from the separator column as the current selection
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
 }
 
 impl Widget for HeatmapCell<'_> {
@@ -180,15 +341,25 @@ impl Widget for HeatmapCell<'_> {
             _ => '\u{2588}',             // Full block
         };
 
-        let color = self.theme.usage_color(self.usage);
+        let color = self.theme.heat_color(self.usage);
         let style = Style::default().fg(color);
 
         // Render block character (2 chars total: block + space)
         buf.set_string(area.x, area.y, char.to_string(), style);
 
-        // Add separator if space allows
+        // Add separator (or marker) if space allows
         if area.width >= 2 {
-            buf.set_string(area.x + 1, area.y, " ", self.theme.dim_style());
+            if self.selected {
+                let cursor_style = Style::default()
+                    .fg(self.theme.text_highlight)
+                    .add_modifier(Modifier::BOLD);
+                buf.set_string(area.x + 1, area.y, "\u{25c2}", cursor_style);
+            } else if self.marked {
+                let marker_style = Style::default().fg(self.theme.text_highlight);
+                buf.set_string(area.x + 1, area.y, "\u{2022}", marker_style);
+            } else {
+                buf.set_string(area.x + 1, area.y, " ", self.theme.dim_style());
+            }
         }
     }
 }
@@ -199,6 +370,7 @@ pub struct GroupHeader<'a> {
     usage: f32,
     core_count: usize,
     theme: &'a Theme,
+    selected: bool,
 }
 
 impl<'a> GroupHeader<'a> {
@@ -208,8 +380,15 @@ impl<'a> GroupHeader<'a> {
             usage,
             core_count,
             theme,
+            selected: false,
         }
     }
+
+    /// Mark the header as the current selection
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
 }
 
 impl Widget for GroupHeader<'_> {
@@ -219,13 +398,19 @@ impl Widget for GroupHeader<'_> {
         }
 
         let header = format!(
-            " {} ({} cores) - {:.1}% ",
-            self.name, self.core_count, self.usage
+            "{}{} ({} cores) - {:.1}% ",
+            if self.selected { ">" } else { " " },
+            self.name,
+            self.core_count,
+            self.usage
         );
 
-        let style = Style::default()
-            .fg(self.theme.header_fg)
-            .bg(self.theme.usage_color(self.usage));
+        let mut style = Style::default()
+            .fg(self.theme.heat_text_color(self.usage))
+            .bg(self.theme.heat_color(self.usage));
+        if self.selected {
+            style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        }
 
         buf.set_string(area.x, area.y, &header, style);
 
@@ -0,0 +1,232 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Widget},
+};
+
+use crate::stats::{CoreUsage, CpuBreakdown, ProcessUsage};
+use crate::topology::CpuCore;
+use crate::ui::theme::Theme;
+use crate::ui::widgets::UsageSparkline;
+
+/// Panel width including borders
+pub const DETAIL_PANEL_WIDTH: u16 = 38;
+
+/// Rows used by the history sparkline
+const HISTORY_HEIGHT: u16 = 3;
+
+/// Width of the label column
+const LABEL_WIDTH: usize = 14;
+
+/// Topology, time breakdown, frequency, temperature, recent history and
+/// busiest processes of the selected CPU
+pub struct DetailPanel<'a> {
+    core: &'a CpuCore,
+    theme: &'a Theme,
+    usage: Option<&'a CoreUsage>,
+    breakdown: Option<&'a CpuBreakdown>,
+    temperature: Option<f32>,
+    series: &'a [f32],
+    processes: &'a [ProcessUsage],
+    local: bool,
+}
+
+impl<'a> DetailPanel<'a> {
+    pub fn new(core: &'a CpuCore, theme: &'a Theme) -> Self {
+        Self {
+            core,
+            theme,
+            usage: None,
+            breakdown: None,
+            temperature: None,
+            series: &[],
+            processes: &[],
+            local: false,
+        }
+    }
+
+    /// Current usage, steal, guest and frequency
+    pub fn usage(mut self, usage: Option<&'a CoreUsage>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Per-state time split from /proc/stat
+    pub fn breakdown(mut self, breakdown: Option<&'a CpuBreakdown>) -> Self {
+        self.breakdown = breakdown;
+        self
+    }
+
+    /// Sensor temperature of the CPU's CCD
+    pub fn temperature(mut self, celsius: Option<f32>) -> Self {
+        self.temperature = celsius;
+        self
+    }
+
+    /// Usage history, oldest first
+    pub fn history(mut self, series: &'a [f32]) -> Self {
+        self.series = series;
+        self
+    }
+
+    /// Busiest processes on the CPU, busiest first
+    pub fn processes(mut self, processes: &'a [ProcessUsage]) -> Self {
+        self.processes = processes;
+        self
+    }
+
+    /// Whether the CPU belongs to this host; breakdown, temperature and
+    /// processes are only collected locally
+    pub fn local(mut self, local: bool) -> Self {
+        self.local = local;
+        self
+    }
+
+    fn field(&self, label: &str, value: String) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(format!("{:<LABEL_WIDTH$}", label), self.theme.dim_style()),
+            Span::styled(value, self.theme.text_style()),
+        ])
+    }
+
+    /// "name value" pairs on one line, values colored by their share
+    fn shares(&self, shares: &[(&str, f32)]) -> Line<'static> {
+        let mut spans = vec![Span::raw(" ")];
+        for (name, value) in shares {
+            spans.push(Span::styled(format!(" {} ", name), self.theme.dim_style()));
+            spans.push(Span::styled(
+                format!("{:.1}", value),
+                self.theme.bar_style(*value),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    fn heading(&self, title: &str) -> Line<'static> {
+        Line::styled(
+            title.to_string(),
+            Style::default()
+                .fg(self.theme.text_highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+}
+
+/// Draw lines from row `y` down, clipped to `area`; returns the next free row
+fn draw_lines(lines: &[Line], area: Rect, mut y: u16, buf: &mut Buffer) -> u16 {
+    for line in lines {
+        if y >= area.y + area.height {
+            break;
+        }
+        buf.set_line(area.x, y, line, area.width);
+        y += 1;
+    }
+    y
+}
+
+impl Widget for DetailPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" CPU {} ", self.core.id))
+            .border_style(Style::default().fg(self.theme.border))
+            .title_style(
+                Style::default()
+                    .fg(self.theme.text_highlight)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if inner.height == 0 || inner.width == 0 {
+            return;
+        }
+
+        let core = self.core;
+        let mut lines = vec![
+            self.field("Physical core", core.physical_id.to_string()),
+            self.field(
+                "SMT sibling",
+                core.smt_sibling
+                    .map_or_else(|| String::from("none"), |s| format!("CPU {}", s)),
+            ),
+            self.field("CCD / CCX", format!("{} / {}", core.ccd_id, core.ccx_id)),
+            self.field("NUMA node", core.numa_node.to_string()),
+            self.field("Socket", core.package_id.to_string()),
+            Line::default(),
+        ];
+
+        let usage = self.usage.map_or(0.0, |u| u.usage_percent);
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<LABEL_WIDTH$}", "Usage"), self.theme.dim_style()),
+            Span::styled(format!("{:.1}%", usage), self.theme.bar_style(usage)),
+        ]));
+        match self.breakdown {
+            Some(b) => {
+                lines.push(self.shares(&[("user", b.user), ("nice", b.nice), ("sys", b.system)]));
+                lines.push(self.shares(&[
+                    ("iowait", b.iowait),
+                    ("irq", b.irq),
+                    ("softirq", b.softirq),
+                ]));
+                lines.push(self.shares(&[("steal", b.steal), ("guest", b.guest)]));
+            }
+            None => {
+                let (steal, guest) = self
+                    .usage
+                    .map_or((0.0, 0.0), |u| (u.steal_percent, u.guest_percent));
+                lines.push(self.shares(&[("steal", steal), ("guest", guest)]));
+            }
+        }
+        let frequency = self
+            .usage
+            .and_then(|u| u.frequency_mhz)
+            .map_or_else(|| String::from("-"), |mhz| format!("{} MHz", mhz));
+        let temperature = self.temperature.map_or_else(
+            || String::from("-"),
+            |t| format!("{:.1}\u{b0}C (CCD {})", t, core.ccd_id),
+        );
+        lines.push(self.field("Frequency", frequency));
+        lines.push(self.field("Temperature", temperature));
+        lines.push(Line::default());
+        lines.push(self.heading("History"));
+
+        let mut y = draw_lines(&lines, inner, inner.y, buf);
+
+        let bottom = inner.y + inner.height;
+        if y < bottom {
+            let height = HISTORY_HEIGHT.min(bottom - y);
+            UsageSparkline::new(self.series, self.theme)
+                .render(Rect::new(inner.x, y, inner.width, height), buf);
+            y += height;
+        }
+
+        let mut lines = vec![Line::default(), self.heading("Top processes")];
+        if !self.local {
+            lines.push(Line::styled(
+                "  Local sessions only",
+                self.theme.dim_style(),
+            ));
+        } else if self.processes.is_empty() {
+            lines.push(Line::styled("  None sampled yet", self.theme.dim_style()));
+        } else {
+            lines.push(Line::styled(
+                format!("{:>7} {:>6}  {}", "PID", "CPU%", "NAME"),
+                self.theme.dim_style(),
+            ));
+            for process in self.processes {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{:>7} ", process.pid), self.theme.dim_style()),
+                    Span::styled(
+                        format!("{:>6.1}", process.usage_percent),
+                        self.theme.bar_style(process.usage_percent),
+                    ),
+                    Span::styled(format!("  {}", process.name), self.theme.text_style()),
+                ]));
+            }
+        }
+        draw_lines(&lines, inner, y, buf);
+    }
+}
@@ -0,0 +1,103 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Widget},
+};
+
+use crate::stats::GuestPlacement;
+use crate::topology::{format_cpu_list, ZenTopology};
+use crate::ui::theme::Theme;
+
+/// Maximum number of guest rows shown in the panel
+pub const GUEST_PANEL_MAX_ROWS: usize = 8;
+
+/// Summary of guest vCPU placement, flagging guests that span CCDs/NUMA nodes
+pub struct GuestPanel<'a> {
+    topology: &'a ZenTopology,
+    guests: &'a [GuestPlacement],
+    theme: &'a Theme,
+}
+
+impl<'a> GuestPanel<'a> {
+    pub fn new(topology: &'a ZenTopology, guests: &'a [GuestPlacement], theme: &'a Theme) -> Self {
+        Self {
+            topology,
+            guests,
+            theme,
+        }
+    }
+
+    /// Panel height including borders
+    pub fn height(guest_count: usize) -> u16 {
+        guest_count.clamp(1, GUEST_PANEL_MAX_ROWS) as u16 + 2
+    }
+}
+
+impl Widget for GuestPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Guests ({}) ", self.guests.len()))
+            .border_style(Style::default().fg(self.theme.border))
+            .title_style(
+                Style::default()
+                    .fg(self.theme.text_highlight)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if inner.height == 0 || inner.width == 0 {
+            return;
+        }
+
+        if self.guests.is_empty() {
+            buf.set_string(
+                inner.x,
+                inner.y,
+                "No QEMU/KVM guests found",
+                self.theme.dim_style(),
+            );
+            return;
+        }
+
+        let warning_style = Style::default()
+            .fg(self.theme.bar_critical)
+            .add_modifier(Modifier::BOLD);
+
+        for (i, guest) in self.guests.iter().take(inner.height as usize).enumerate() {
+            let y = inner.y + i as u16;
+            let cpus = guest.host_cpus();
+            let ccds = self.topology.ccds_for_cpus(&cpus);
+            let numa_nodes = self.topology.numa_nodes_for_cpus(&cpus);
+
+            let line = format!(
+                "{:<20} {:>3} vCPUs | CCD {} | NUMA {} ",
+                guest.name,
+                guest.vcpus.len(),
+                format_cpu_list(&ccds),
+                format_cpu_list(&numa_nodes),
+            );
+            let (x, _) = buf.set_stringn(
+                inner.x,
+                y,
+                &line,
+                inner.width as usize,
+                self.theme.text_style(),
+            );
+
+            let mut warnings = Vec::new();
+            if ccds.len() > 1 {
+                warnings.push(format!("spans {} CCDs", ccds.len()));
+            }
+            if numa_nodes.len() > 1 {
+                warnings.push(format!("spans {} NUMA nodes", numa_nodes.len()));
+            }
+            if !warnings.is_empty() {
+                let remaining = (inner.x + inner.width).saturating_sub(x) as usize;
+                buf.set_stringn(x, y, warnings.join(", "), remaining, warning_style);
+            }
+        }
+    }
+}
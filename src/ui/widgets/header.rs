@@ -1,12 +1,15 @@
+use std::time::Duration;
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Style,
+    style::{Modifier, Style},
     widgets::Widget,
 };
 
 use crate::app::{DisplayMode, ViewMode};
 use crate::topology::ZenTopology;
+use crate::ui::hitmap::{HitMap, Target};
 use crate::ui::theme::Theme;
 
 /// Application header widget
@@ -16,7 +19,12 @@ pub struct Header<'a> {
     display_mode: DisplayMode,
     show_smt: bool,
     total_usage: f32,
+    steal_warning: Option<f32>,
+    source_status: Option<String>,
+    refresh_interval: Option<Duration>,
+    paused: bool,
     theme: &'a Theme,
+    hits: Option<&'a HitMap>,
 }
 
 impl<'a> Header<'a> {
@@ -34,9 +42,39 @@ impl<'a> Header<'a> {
             display_mode,
             show_smt,
             total_usage,
+            steal_warning: None,
+            source_status: None,
+            refresh_interval: None,
+            paused: false,
             theme,
+            hits: None,
         }
     }
+
+    /// Show a steal time warning with the given aggregate percentage
+    pub fn steal_warning(mut self, steal: Option<f32>) -> Self {
+        self.steal_warning = steal;
+        self
+    }
+
+    /// Show the sampling interval and whether the display is frozen
+    pub fn refresh(mut self, interval: Duration, paused: bool) -> Self {
+        self.refresh_interval = Some(interval);
+        self.paused = paused;
+        self
+    }
+
+    /// Show replay position or recording marker on the title line
+    pub fn source_status(mut self, status: Option<String>) -> Self {
+        self.source_status = status;
+        self
+    }
+
+    /// Register the view, SMT and mode labels as click targets
+    pub fn hit_map(mut self, hits: &'a HitMap) -> Self {
+        self.hits = Some(hits);
+        self
+    }
 }
 
 impl Widget for Header<'_> {
@@ -74,12 +112,29 @@ impl Widget for Header<'_> {
             );
         }
 
+        // Replay/record status, right-aligned on the title line
+        if let Some(status) = &self.source_status {
+            let status = format!(" {} ", status);
+            let status_x = (area.x + area.width)
+                .saturating_sub(status.len() as u16)
+                .max(area.x);
+            buf.set_string(
+                status_x,
+                area.y,
+                &status,
+                title_style.add_modifier(Modifier::BOLD),
+            );
+        }
+
         // Line 2: View mode and total usage
         if area.height >= 2 {
             let mode_str = match self.view_mode {
                 ViewMode::Core => "[c]ore",
                 ViewMode::Ccd => "cc[d]",
                 ViewMode::Nps => "[n]ps",
+                ViewMode::Cgroup => "[C]group",
+                ViewMode::Irq => "[i]rq",
+                ViewMode::Cluster => "cl[u]ster",
             };
 
             let smt_str = if self.show_smt { "All" } else { "Physical" };
@@ -88,13 +143,41 @@ impl Widget for Header<'_> {
                 DisplayMode::Full => "Full",
                 DisplayMode::Compact => "Compact",
                 DisplayMode::Heatmap => "Heatmap",
+                DisplayMode::Sparkline => "Sparkline",
+                DisplayMode::Waterfall => "Waterfall",
+            };
+
+            let refresh_str = match self.refresh_interval {
+                Some(interval) => format!(
+                    " | Refresh: {}{}",
+                    format_interval(interval),
+                    if self.paused { " PAUSED" } else { "" }
+                ),
+                None => String::new(),
             };
 
+            let view_label = format!("View: {}", mode_str);
+            let smt_label = format!("Cores: {}", smt_str);
+            let mode_label = format!("Mode: [m]{}", display_str);
             let status = format!(
-                " View: {} | Cores: {} | Mode: [m]{} | Total: {:.1}% | [h]elp [q]uit ",
-                mode_str, smt_str, display_str, self.total_usage
+                " {} | {} | {} | Total: {:.1}%{} | [?]help [q]uit ",
+                view_label, smt_label, mode_label, self.total_usage, refresh_str
             );
 
+            if let Some(hits) = self.hits {
+                let right = area.x + area.width;
+                let mut x = area.x + 1;
+                for (label, target) in [
+                    (&view_label, Target::ViewLabel),
+                    (&smt_label, Target::SmtLabel),
+                    (&mode_label, Target::ModeLabel),
+                ] {
+                    let width = (label.len() as u16).min(right.saturating_sub(x));
+                    hits.add(Rect::new(x, area.y + 1, width, 1), target);
+                    x += label.len() as u16 + 3; // " | "
+                }
+            }
+
             let status_style = Style::default()
                 .fg(self.theme.text_normal)
                 .bg(self.theme.border);
@@ -110,6 +193,31 @@ impl Widget for Header<'_> {
                     status_style,
                 );
             }
+
+            // Steal warning, right-aligned (overrides the status text if narrow)
+            if let Some(steal) = self.steal_warning {
+                let warning = format!(" STEAL {:.1}% ", steal);
+                let warning_x = (area.x + area.width)
+                    .saturating_sub(warning.len() as u16)
+                    .max(area.x);
+                let warning_style = Style::default()
+                    .fg(self.theme.header_fg)
+                    .bg(self.theme.bar_steal)
+                    .add_modifier(Modifier::BOLD);
+                buf.set_string(warning_x, area.y + 1, &warning, warning_style);
+            }
         }
     }
 }
+
+/// Format an interval as "250ms", "1s" or "2.5s"
+fn format_interval(interval: Duration) -> String {
+    let ms = interval.as_millis();
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms % 1000 == 0 {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{:.1}s", interval.as_secs_f64())
+    }
+}
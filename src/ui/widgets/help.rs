@@ -5,33 +5,44 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
 
+use crate::app::Scroll;
+use crate::ui::hitmap::HitMap;
 use crate::ui::theme::Theme;
+use crate::ui::widgets::ScrollIndicator;
 
 /// Help overlay widget
+///
+/// Scrolls when the terminal is too short to show every line.
 pub struct HelpOverlay<'a> {
     theme: &'a Theme,
+    scroll_offset: usize,
+    hits: Option<&'a HitMap>,
 }
 
 impl<'a> HelpOverlay<'a> {
     pub fn new(theme: &'a Theme) -> Self {
-        Self { theme }
+        Self {
+            theme,
+            scroll_offset: 0,
+            hits: None,
+        }
+    }
+
+    /// First help line shown
+    pub fn scroll(mut self, offset: usize) -> Self {
+        self.scroll_offset = offset;
+        self
+    }
+
+    /// Record the scroll extent so scroll keys stop at the last line
+    pub fn hit_map(mut self, hits: &'a HitMap) -> Self {
+        self.hits = Some(hits);
+        self
     }
 }
 
 impl Widget for HelpOverlay<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        // Calculate centered popup area
-        let popup_width = 50.min(area.width.saturating_sub(4));
-        let popup_height = 18.min(area.height.saturating_sub(4));
-
-        let popup_x = area.x + (area.width.saturating_sub(popup_width)) / 2;
-        let popup_y = area.y + (area.height.saturating_sub(popup_height)) / 2;
-
-        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
-
-        // Clear background
-        Clear.render(popup_area, buf);
-
         let help_text = r#"
   Keyboard Shortcuts
   ──────────────────
@@ -40,20 +51,65 @@ impl Widget for HelpOverlay<'_> {
     c    Core view (individual CPUs)
     d    CCD view (grouped by CCD)
     n    NPS view (grouped by NPS node)
+    C    cgroup view (per-cgroup usage)
+    i    IRQ view (interrupt rates per core/CCD)
+    u    Cluster overview (connect to several hosts)
+         C, i and v describe this host only and are
+         off when replaying or connected to agents
 
   Display:
     s    Toggle SMT (all threads / physical only)
-    m    Cycle display mode (Full/Compact/Heatmap)
+    m    Cycle display mode
+         (Full/Compact/Heatmap/Sparkline/Waterfall)
+    v    Toggle guest vCPU placement overlay
+    t    Cycle color theme
 
   Navigation:
-    j/↓  Scroll down
-    k/↑  Scroll up
+    ↑↓←→ hjkl  Move cursor over CPUs (groups in
+               grouped sparkline/waterfall modes)
+    PgUp/PgDn  Move or scroll a page
+    g/G Home/End  Jump to top / bottom
+    Enter  Toggle history chart / open selected host
+    p    Toggle detail panel for the selected CPU
+
+  Mouse:
+    Click  Select CPU/group, focus socket pane, or
+           switch view/cores/mode in the status line
+    Wheel  Scroll
+
+  Sampling:
+    Space  Pause / resume (freeze display or replay)
+    +/-  Faster / slower (refresh or replay speed)
+
+  Replay:
+    .    Step one sample
+    [/]  Seek backward / forward
 
   Other:
-    h/?  Toggle this help
+    w    Save view/display/theme/refresh to config
+    ?    Toggle this help (↑↓ PgUp/PgDn scroll it)
     q    Quit
 "#;
 
+        // As tall as the text, leaving a row above and below the popup
+        let content = help_text.lines().count();
+        let popup_width = 56.min(area.width.saturating_sub(4));
+        let popup_height = (content as u16 + 2).min(area.height.saturating_sub(2));
+
+        let popup_x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+        let popup_y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+
+        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+        // Clear background
+        Clear.render(popup_area, buf);
+
+        let viewport = popup_height.saturating_sub(2) as usize;
+        let offset = match self.hits {
+            Some(hits) => hits.help_scroll(self.scroll_offset, content, viewport),
+            None => self.scroll_offset.min(content.saturating_sub(viewport)),
+        };
+
         let block = Block::default()
             .title(" Help ")
             .borders(Borders::ALL)
@@ -62,9 +118,17 @@ impl Widget for HelpOverlay<'_> {
         let paragraph = Paragraph::new(help_text)
             .block(block)
             .style(self.theme.text_style())
-            .alignment(Alignment::Left);
+            .alignment(Alignment::Left)
+            .scroll((offset as u16, 0));
 
         paragraph.render(popup_area, buf);
+
+        let scroll = Scroll {
+            offset,
+            content,
+            viewport,
+        };
+        ScrollIndicator::new(scroll, self.theme).render(popup_area, buf);
     }
 }
 
@@ -0,0 +1,84 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    symbols,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Widget},
+};
+
+use crate::ui::theme::Theme;
+
+/// Full-height usage time-series chart
+pub struct HistoryChart<'a> {
+    title: &'a str,
+    series: &'a [f32],
+    /// Seconds before the newest sample, matching `series`
+    offsets: &'a [f64],
+    theme: &'a Theme,
+}
+
+impl<'a> HistoryChart<'a> {
+    pub fn new(title: &'a str, series: &'a [f32], offsets: &'a [f64], theme: &'a Theme) -> Self {
+        Self {
+            title,
+            series,
+            offsets,
+            theme,
+        }
+    }
+}
+
+impl Widget for HistoryChart<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let points: Vec<(f64, f64)> = self
+            .offsets
+            .iter()
+            .zip(self.series)
+            .map(|(&t, &usage)| (t, usage as f64))
+            .collect();
+
+        let span = self.offsets.first().map_or(0.0, |t| -t).max(1.0);
+        let latest = self.series.last().copied().unwrap_or(0.0);
+
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(self.theme.usage_color(latest)))
+            .data(&points);
+
+        let label_style = self.theme.dim_style();
+        let x_axis = Axis::default()
+            .style(label_style)
+            .bounds([-span, 0.0])
+            .labels([
+                Span::styled(format!("-{:.0}s", span), label_style),
+                Span::styled(format!("-{:.0}s", span / 2.0), label_style),
+                Span::styled("now", label_style),
+            ]);
+        let y_axis = Axis::default()
+            .style(label_style)
+            .bounds([0.0, 100.0])
+            .labels([
+                Span::styled("0%", label_style),
+                Span::styled("50%", label_style),
+                Span::styled("100%", label_style),
+            ]);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} - {:.1}% ", self.title, latest))
+            .border_style(Style::default().fg(self.theme.border))
+            .title_style(
+                Style::default()
+                    .fg(self.theme.text_highlight)
+                    .add_modifier(Modifier::BOLD),
+            );
+
+        Chart::new(vec![dataset])
+            .block(block)
+            .x_axis(x_axis)
+            .y_axis(y_axis)
+            .render(area, buf);
+    }
+}
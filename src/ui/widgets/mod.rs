@@ -1,7 +1,17 @@
 pub mod cpu_bar;
+pub mod detail_panel;
+pub mod guest_panel;
 pub mod header;
 pub mod help;
+pub mod history_chart;
+pub mod scroll_indicator;
+pub mod sparkline;
 
 pub use cpu_bar::{CompactCpuBar, CpuBar, GroupHeader, HeatmapCell};
+pub use detail_panel::{DetailPanel, DETAIL_PANEL_WIDTH};
+pub use guest_panel::GuestPanel;
 pub use header::Header;
 pub use help::HelpOverlay;
+pub use history_chart::HistoryChart;
+pub use scroll_indicator::ScrollIndicator;
+pub use sparkline::UsageSparkline;
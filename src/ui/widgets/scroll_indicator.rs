@@ -0,0 +1,55 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    symbols::scrollbar,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget},
+};
+
+use crate::app::Scroll;
+use crate::ui::theme::Theme;
+
+/// Scrollbar on the right border and "first-last/total" on the bottom
+/// border of a bordered pane, when its content does not fit
+pub struct ScrollIndicator<'a> {
+    scroll: Scroll,
+    theme: &'a Theme,
+}
+
+impl<'a> ScrollIndicator<'a> {
+    pub fn new(scroll: Scroll, theme: &'a Theme) -> Self {
+        Self { scroll, theme }
+    }
+}
+
+impl Widget for ScrollIndicator<'_> {
+    /// `area` is the whole pane, borders included
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let scroll = self.scroll;
+        if scroll.max_offset() == 0 || area.height < 3 {
+            return;
+        }
+
+        // ScrollbarState positions run up to the last line at the top of the
+        // viewport; ours stop at the last line at the bottom
+        let mut state = ScrollbarState::new(scroll.max_offset() + 1)
+            .viewport_content_length(scroll.viewport)
+            .position(scroll.offset);
+        let track = Rect::new(area.x, area.y + 1, area.width, area.height - 2);
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .symbols(scrollbar::VERTICAL)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_style(Style::default().fg(self.theme.border))
+            .thumb_style(self.theme.text_style())
+            .render(track, buf, &mut state);
+
+        let last = (scroll.offset + scroll.viewport).min(scroll.content);
+        let position = format!(" {}-{}/{} ", scroll.offset + 1, last, scroll.content);
+        let width = position.len() as u16;
+        if area.width > width + 2 {
+            let x = area.x + area.width - width - 1;
+            buf.set_string(x, area.y + area.height - 1, &position, self.theme.dim_style());
+        }
+    }
+}
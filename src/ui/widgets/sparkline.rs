@@ -0,0 +1,50 @@
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+
+use crate::ui::theme::Theme;
+
+/// Lower eighth block glyphs, indexed by filled eighths (0-8)
+const BLOCKS: [char; 9] = [
+    ' ', '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}',
+    '\u{2588}',
+];
+
+/// Usage history sparkline, newest sample at the right edge
+///
+/// Each column is colored by its own usage, so bursts stand out even when
+/// the current value is low.
+pub struct UsageSparkline<'a> {
+    series: &'a [f32],
+    theme: &'a Theme,
+}
+
+impl<'a> UsageSparkline<'a> {
+    pub fn new(series: &'a [f32], theme: &'a Theme) -> Self {
+        Self { series, theme }
+    }
+}
+
+impl Widget for UsageSparkline<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let width = area.width as usize;
+        let visible = &self.series[self.series.len().saturating_sub(width)..];
+        let x_start = area.x + (width - visible.len()) as u16;
+        let levels = area.height as usize * 8;
+
+        for (i, &usage) in visible.iter().enumerate() {
+            let x = x_start + i as u16;
+            let filled = ((usage.clamp(0.0, 100.0) / 100.0) * levels as f32).round() as usize;
+            let style = Style::default().fg(self.theme.usage_color(usage));
+
+            for row in 0..area.height as usize {
+                // Row 0 is the bottom line
+                let eighths = filled.saturating_sub(row * 8).min(8);
+                let y = area.y + area.height - 1 - row as u16;
+                buf.set_string(x, y, BLOCKS[eighths].to_string(), style);
+            }
+        }
+    }
+}